@@ -1,99 +1,179 @@
-#[doc="
+#![doc="
 BKTree
 In a BK tree, the nodes represent words and the edges are weighted with the 
-Levenshtein distance between adjacent nodes' words.
+Levenshtein distance between adjacent nodes' words. The tree is generic over
+the type of the items it stores and the `Metric` used to weight the edges, so
+it can also answer nearest neighbour queries over other data, such as the
+Hamming distance between 64-bit image hashes. By default it stores words and
+uses the Levenshtein distance.
 
 root: the root node of the BK tree
+metric: the distance between two items used to weight the edges
+max_distance: the search radius used when the tree is queried through the
+  `Corrector` trait, either fixed or depending on the length of the word
+longest: the number of chars in the longest word added, which caps the radius
 "]
 
-use std::cmp::min;
 use std::collections::HashMap;
 
-pub struct Node {
-    word: String,
+use spellcore::{Correction, Corrector, Suggestion};
+use spellcore::distance::MaxDistance;
+
+use crate::metric::{Levenshtein, Metric};
+
+pub const DEFAULT_MAX_DISTANCE: MaxDistance = MaxDistance::Fixed(2);
+
+pub struct Node<T> {
+    item: T,
     freq: usize,
-    children: HashMap<usize, Node>,
+    children: HashMap<usize, Node<T>>,
 }
 
-impl Node {
-    pub fn new(word: String,
+impl<T> Node<T> {
+    pub fn new(item: T,
                freq: usize,
-               children: HashMap<usize, Node>)
-               -> Node {
+               children: HashMap<usize, Node<T>>)
+               -> Node<T> {
         Node {
-            word: word,
-            freq: freq,
-            children: children,
+            item,
+            freq,
+            children,
         }
     }
+
+    pub fn item(&self) -> &T {
+        &self.item
+    }
+
+    pub fn freq(&self) -> usize {
+        self.freq
+    }
 }
 
-pub struct BKTree {
-    root: Node,
+pub struct BKTree<T = String, M = Levenshtein> {
+    root: Node<T>,
+    metric: M,
+    max_distance: MaxDistance,
+    longest: usize,
 }
 
 impl BKTree {
 
-    pub fn new(root: Node) -> BKTree {
+    pub fn new(root: Node<String>) -> BKTree {
+        let longest = root.item.chars().count();
+        BKTree { longest, ..BKTree::with_metric(root, Levenshtein) }
+    }
+
+}
+
+impl<T, M: Metric<T>> BKTree<T, M> {
+
+    pub fn with_metric(root: Node<T>, metric: M) -> BKTree<T, M> {
         BKTree {
-            root: root,
+            root,
+            metric,
+            max_distance: DEFAULT_MAX_DISTANCE,
+            longest: 0,
         }
     }
-    
-    pub fn add(&mut self, word: &str) {
-        traverse_tree(&mut self.root, word);
+
+    pub fn insert(&mut self, item: T) {
+        traverse_tree(&mut self.root, item, &self.metric);
+    }
+
+    pub fn set_max_distance(&mut self, max_distance: MaxDistance) {
+        self.max_distance = max_distance;
     }
 
-    pub fn search(&self, word: &str, d: &usize) -> &str {
+    // All items within distance d of item, closest and most frequent first
+    pub fn find(&self, item: &T, d: usize) -> Vec<(usize, &Node<T>)> {
         let mut v = Vec::new();
-        recursive_search(&self.root, &mut v, word, d);
-
-        if v.len() == 0 {
-            return "-";
-        } else {
-
-            v.sort_by(|a, b|  {
-                
-                // Sort by distance
-                if a.0 != b.0 {
-                    a.0.cmp(&b.0)
-                }
-
-                // If equal distance, sort by word frequency 
-                else {
-                    b.1.freq.cmp(&a.1.freq)
-                }
-            });
-
-            // if there is an exact match (ie. dist == 0)
-            if v[0].0 == 0 {
-                return "";
-            }
+        recursive_search(&self.root, &mut v, item, &d, &self.metric);
+        v.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| b.1.freq.cmp(&a.1.freq)));
+        v
+    }
 
-            else {
-                return &v[0].1.word;
-            }
+}
+
+impl<M: Metric<String>> BKTree<String, M> {
+    
+    pub fn add(&mut self, word: &str) {
+        self.longest = self.longest.max(word.chars().count());
+        self.insert(word.to_string());
+    }
+
+    pub fn search(&self, word: &str, d: &usize) -> Correction {
+        // the first candidate is an exact match (ie. dist == 0) if there is one
+        match self.candidates(word, d).into_iter().next() {
+            Some(best) => best.into_correction(),
+            None => Correction::NoSuggestion,
         }
     }
 
+    // All words within distance d of word, best first
+    pub fn candidates(&self, word: &str, d: &usize) -> Vec<Suggestion> {
+        // Sort by distance, then by word frequency
+        let mut v: Vec<Suggestion> = self.find(&word.to_string(), *d).iter()
+            .map(|&(dist, node)| Suggestion::new(&node.item, dist, node.freq))
+            .collect();
+        v.sort_by(Suggestion::rank);
+        v
+    }
+
+}
+
+impl<M: Metric<String>> Corrector for BKTree<String, M> {
+
+    fn check(&self, word: &str) -> bool {
+        !self.candidates(word, &0).is_empty()
+    }
+
+    fn suggest(&self, word: &str) -> Correction {
+        self.search(word, &self.max_distance.within(word, self.longest))
+    }
+
+    fn suggest_top_k(&self, word: &str, k: usize) -> Vec<Suggestion> {
+        let mut v = self.candidates(word, &self.max_distance.within(word, self.longest));
+        v.truncate(k);
+        v
+    }
+
+    fn suggest_within(&self, word: &str, max_distance: usize, k: usize) -> Vec<Suggestion> {
+        let d = max_distance.min(self.max_distance.within(word, self.longest));
+        let mut v = self.candidates(word, &d);
+        v.truncate(k);
+        v
+    }
+
 }
 
-fn traverse_tree(node: &mut Node, word: &str) {
-    let dist = levenshtein_distance(&node.word, word);
+fn traverse_tree<T, M: Metric<T>>(node: &mut Node<T>, item: T, metric: &M) {
+    let dist = metric.distance(&node.item, &item);
     if dist == 0 {
         node.freq += 1;
         return;
     }
-    if node.children.contains_key(&dist) {
-        traverse_tree(node.children.get_mut(&dist).expect("Impossible"), word);
-    } else {
-        let child = Node::new(word.to_string(), 1, HashMap::new());
-        node.children.insert(dist, child);
+    match node.children.get_mut(&dist) {
+        Some(child) => traverse_tree(child, item, metric),
+        None => {
+            let child = Node::new(item, 1, HashMap::new());
+            node.children.insert(dist, child);
+        }
     }
 }
 
-fn recursive_search<'a>(node: &'a Node, v: &mut Vec<(usize, &'a Node)>, word: &str, d: &usize) {
-    let cur_dist = levenshtein_distance(&node.word, word);
+fn recursive_search<'a, T, M: Metric<T>>(node: &'a Node<T>,
+                                         v: &mut Vec<(usize, &'a Node<T>)>,
+                                         item: &T,
+                                         d: &usize,
+                                         metric: &M) {
+    // Neither this node nor any child can be within d of item if this node is
+    // further than d plus the largest edge to a child, so stop measuring there
+    let max_key = node.children.keys().max().cloned().unwrap_or(0);
+    let cur_dist = match metric.distance_within(&node.item, item, d.saturating_add(max_key)) {
+        Some(dist) => dist,
+        None => return,
+    };
     let mut min_dist = 0;
 
     // Check that cur_dist > d first to prevent usize from underflowing
@@ -101,71 +181,41 @@ fn recursive_search<'a>(node: &'a Node, v: &mut Vec<(usize, &'a Node)>, word: &s
         min_dist = cur_dist - d;
     }
 
-    let max_dist = cur_dist + d;
+    let max_dist = cur_dist.saturating_add(*d);
 
     if cur_dist <= *d {
-        v.push((cur_dist, &node));
-    }
-
-    for key in node.children.keys() {
-        if key >= &min_dist && key <= &max_dist {
-            recursive_search(&node.children.get(key).expect("Impossible"), v, word, d);
+        if let Some(dist) = metric.reported(&node.item, item, cur_dist, *d) {
+            v.push((dist, node));
         }
     }
-}
-
-fn levenshtein_distance(w1: &str, w2: &str) -> usize {
-    let len1 = w1.len();
-    let len2 = w2.len();
 
-    if len1 == 0 {
-        return len2;
-    }
-    if len2 == 0 {
-        return len1;
-    }
-
-    let mut d: Vec<Vec<usize>> = Vec::new();
-
-    for i in 0..(len1+1) {
-        d.push(vec![i]);
-        for _ in 1..(len2+1) {
-            d[i].push(0);
-        }
-    }
-    for i in 0..(len2+1) {
-        d[0][i] = i;
-    }
-
-    for i in 1..(len1+1) {
-        for j in 1..(len2+1) {
-            let mut same = 1;
-            if w1[(i-1)..i] == w2[(j-1)..j] {
-                same = 0;
-            }
-            d[i][j] = min(min(d[i-1][j]+1, d[i][j-1]+1), d[i-1][j-1]+same);
+    for (key, child) in &node.children {
+        if key >= &min_dist && key <= &max_dist {
+            recursive_search(child, v, item, d, metric);
         }
     }
-    d[len1][len2]
 }
 
 #[cfg(test)]
 mod bk_tree_tests {
 
     use std::collections::HashMap;
+    use spellcore::{Correction, Corrector, Suggestion};
+    use spellcore::distance::{damerau_levenshtein_distance, levenshtein_distance, MaxDistance};
+    use crate::metric::{DamerauLevenshtein, Hamming, Levenshtein, Metric};
     use super::Node;
     use super::BKTree;
 
     #[test]
     fn test_no_need_to_correct_word() {
         let bk = small_fixture();
-        assert_eq!(bk.search("who", &1), "");
+        assert_eq!(bk.search("who", &1), Correction::Known);
     }
 
     #[test]
     fn test_no_suggestions_found() {
         let bk = small_fixture();
-        assert_eq!(bk.search("asjkdghlaksjdghls", &1), "-");
+        assert_eq!(bk.search("asjkdghlaksjdghls", &1), Correction::NoSuggestion);
     }
 
     #[test]
@@ -173,83 +223,194 @@ mod bk_tree_tests {
         // ho should suggest who over how 
         // since who has freq=2 while how has freq=1
         let bk = small_fixture();
-        assert_eq!(bk.search("ho", &1), "who");
+        assert_eq!(bk.search("ho", &1), Correction::suggested("who", 1, 2));
     }
 
     #[test]
     fn test_correct_suggestion() {
         let bk = small_fixture();
-        assert_eq!(bk.search("wherf", &1), "where");
+        assert_eq!(bk.search("wherf", &1), Correction::suggested("where", 1, 2));
     }
 
-    fn small_fixture() -> BKTree {
-        let root = Node::new("what".to_string(), 0, HashMap::new());
-        let mut bk = BKTree::new(root);
-        bk.add("why");
-        bk.add("where");
-        bk.add("where");
-        bk.add("when");
-        bk.add("how");
-        bk.add("who");
-        bk.add("who");
-        bk
+    #[test]
+    fn test_check() {
+        let bk = small_fixture();
+        assert!(bk.check("who"));
+        assert!(!bk.check("ho"));
     }
 
-}
+    #[test]
+    fn test_suggest() {
+        let bk = small_fixture();
+        assert_eq!(bk.suggest("who"), Correction::Known);
+        assert_eq!(bk.suggest("wherf"), Correction::suggested("where", 1, 2));
+        assert_eq!(bk.suggest("asjkdghlaksjdghls"), Correction::NoSuggestion);
+    }
 
-#[cfg(test)]
-mod levenshtein_distance_tests {
+    #[test]
+    fn test_suggest_top_k() {
+        let mut bk = small_fixture();
+        bk.set_max_distance(MaxDistance::Fixed(1));
+        assert_eq!(bk.suggest_top_k("ho", 2),
+                   vec![Suggestion::new("who", 1, 2), Suggestion::new("how", 1, 1)]);
+        assert_eq!(bk.suggest_top_k("ho", 1), vec![Suggestion::new("who", 1, 2)]);
+        assert!(bk.suggest_top_k("asjkdghlaksjdghls", 3).is_empty());
+    }
 
-    use super::levenshtein_distance;
+    #[test]
+    fn test_suggest_top_k_includes_known_word() {
+        let bk = small_fixture();
+        assert_eq!(bk.suggest_top_k("who", 3),
+                   vec![Suggestion::new("who", 0, 2),
+                        Suggestion::new("why", 1, 1),
+                        Suggestion::new("how", 2, 1)]);
+    }
 
     #[test]
-    fn test_hello_world() {
-        assert_eq!(levenshtein_distance("hello", "world"), 4);
+    fn test_ratio_max_distance() {
+        // A quarter of a short word's length only allows one edit, so "ho"
+        // doesn't pick up every three letter word two edits away
+        let mut bk = small_fixture();
+        bk.set_max_distance(MaxDistance::Ratio(0.25));
+        assert_eq!(bk.suggest_top_k("ho", 5),
+                   vec![Suggestion::new("who", 1, 2), Suggestion::new("how", 1, 1)]);
+        assert_eq!(bk.suggest("whxxe"), Correction::NoSuggestion);
+        bk.set_max_distance(MaxDistance::Ratio(0.4));
+        assert_eq!(bk.suggest("whxxe"), Correction::suggested("where", 2, 2));
     }
 
     #[test]
-    fn test_cat_hat() {
-        assert_eq!(levenshtein_distance("cat", "hat"), 1);
+    fn test_huge_max_distance() {
+        let mut bk = small_fixture();
+        bk.set_max_distance(MaxDistance::Fixed(usize::MAX));
+        assert_eq!(bk.suggest("whxxe"), Correction::suggested("where", 2, 2));
+        assert_eq!(bk.find(&"ho".to_string(), usize::MAX).len(),
+                   bk.find(&"ho".to_string(), 100).len());
     }
 
     #[test]
-    fn test_book_back() {
-        assert_eq!(levenshtein_distance("book", "back"), 2);
+    fn test_ties_broken_alphabetically() {
+        let bk = small_fixture();
+        let words: Vec<String> = bk.candidates("whe", &1).into_iter().map(|s| s.word).collect();
+        assert_eq!(words, vec!["who", "when", "why"]);
     }
 
     #[test]
-    fn test_world_wordl() {
-        assert_eq!(levenshtein_distance("world", "wordl"), 2);
+    fn test_levenshtein_prefers_deletion_over_transposition() {
+        // wordl -> word is one deletion, wordl -> world is two substitutions
+        let bk = word_world_fixture(Levenshtein);
+        assert_eq!(bk.search("wordl", &2), Correction::suggested("word", 1, 1));
     }
 
     #[test]
-    fn test_lion_lying() {
-        assert_eq!(levenshtein_distance("lion", "lying"), 3);
+    fn test_damerau_levenshtein_counts_transposition_once() {
+        // wordl -> world is one transposition, and world is more frequent
+        let bk = word_world_fixture(DamerauLevenshtein);
+        assert_eq!(bk.search("wordl", &2), Correction::suggested("world", 1, 2));
+    }
+
+    fn word_world_fixture<M: Metric<String>>(metric: M) -> BKTree<String, M> {
+        let root = Node::new("the".to_string(), 1, HashMap::new());
+        let mut bk = BKTree::with_metric(root, metric);
+        bk.add("word");
+        bk.add("world");
+        bk.add("world");
+        bk
     }
 
     #[test]
-    fn test_astrology_astronomy() {
-        assert_eq!(levenshtein_distance("astrology", "astronomy"), 2);
+    fn test_finds_same_words_as_exhaustive_search() {
+        assert_finds_same_words(Levenshtein, levenshtein_distance);
+        assert_finds_same_words(DamerauLevenshtein, damerau_levenshtein_distance);
+    }
+
+    fn assert_finds_same_words<M: Metric<String>>(metric: M, distance: fn(&str, &str) -> usize) {
+        let words = ["the", "then", "than", "that", "this", "these", "those", "there",
+                     "their", "they", "them", "thee", "three", "throw", "through",
+                     "though", "thought", "tough", "trough", "cough", "bough", "bought",
+                     "hte", "teh", "ac", "ca", "abc"];
+        let root = Node::new(words[0].to_string(), 0, HashMap::new());
+        let mut bk = BKTree::with_metric(root, metric);
+        for w in &words {
+            bk.add(w);
+        }
+        for query in &["th", "thew", "thought", "rough", "xyz", "thorough", "eht", "ac", "cab"] {
+            for d in 0..4 {
+                let mut found: Vec<String> = bk.candidates(query, &d).into_iter()
+                    .map(|s| s.word)
+                    .collect();
+                found.sort();
+                let mut expected: Vec<String> = words.iter()
+                    .filter(|w| distance(w, query) <= d)
+                    .map(|w| w.to_string())
+                    .collect();
+                expected.sort();
+                assert_eq!(expected, found, "{} {}", query, d);
+            }
+        }
     }
 
     #[test]
-    fn test_books_slack() {
-        assert_eq!(levenshtein_distance("books", "slack"), 5);
+    fn test_damerau_levenshtein_finds_every_word_within_distance() {
+        // The restricted distance breaks the triangle inequality here: "ca" is
+        // 3 from "abc", but "ac" is 1 from each of them
+        let root = Node::new("abc".to_string(), 1, HashMap::new());
+        let mut bk = BKTree::with_metric(root, DamerauLevenshtein);
+        bk.add("ca");
+        assert_eq!(bk.candidates("ac", &1),
+                   vec![Suggestion::new("abc", 1, 1), Suggestion::new("ca", 1, 1)]);
+        assert_eq!(bk.candidates("ca", &2), vec![Suggestion::new("ca", 0, 1)]);
+        assert_eq!(bk.candidates("ca", &3).len(), 2);
     }
 
     #[test]
-    fn test_computer_him() {
-        assert_eq!(levenshtein_distance("computer", "him"), 7);
+    fn test_non_ascii_words() {
+        let root = Node::new("naïve".to_string(), 1, HashMap::new());
+        let mut bk = BKTree::new(root);
+        bk.add("café");
+        bk.add("straße");
+        assert_eq!(bk.search("cafe", &2), Correction::suggested("café", 1, 1));
+        assert_eq!(bk.search("naive", &2), Correction::suggested("naïve", 1, 1));
+        assert_eq!(bk.search("strase", &2), Correction::suggested("straße", 1, 1));
     }
 
     #[test]
-    fn test_elephant_elegant() {
-        assert_eq!(levenshtein_distance("elephant", "elegant"), 2);
+    fn test_hamming_nearest_neighbours() {
+        let root = Node::new(0b0000_0000u64, 1, HashMap::new());
+        let mut bk = BKTree::with_metric(root, Hamming);
+        bk.insert(0b1111_0000);
+        bk.insert(0b0000_0011);
+        bk.insert(0b0000_0111);
+        bk.insert(0b0000_0111);
+        bk.insert(0b1111_1111);
+
+        let mut found: Vec<(usize, u64, usize)> = bk.find(&0b0000_0001, 2).iter()
+            .map(|&(dist, node)| (dist, *node.item(), node.freq()))
+            .collect();
+        found.sort();
+        assert_eq!(found, vec![(1, 0b0000_0000, 1), (1, 0b0000_0011, 1), (2, 0b0000_0111, 2)]);
     }
 
     #[test]
-    fn test_onomatopoeia_hyperbole() {
-        assert_eq!(levenshtein_distance("onomatopoeia", "hyperbole"), 10);
+    fn test_boxed_metric() {
+        let root = Node::new("the".to_string(), 1, HashMap::new());
+        let metric: Box<dyn Metric<String>> = Box::new(DamerauLevenshtein);
+        let mut bk = BKTree::with_metric(root, metric);
+        bk.add("fear");
+        assert_eq!(bk.search("fera", &1), Correction::suggested("fear", 1, 1));
     }
-    
+
+    fn small_fixture() -> BKTree {
+        let root = Node::new("what".to_string(), 0, HashMap::new());
+        let mut bk = BKTree::new(root);
+        bk.add("why");
+        bk.add("where");
+        bk.add("where");
+        bk.add("when");
+        bk.add("how");
+        bk.add("who");
+        bk.add("who");
+        bk
+    }
+
 }
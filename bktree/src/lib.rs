@@ -0,0 +1,12 @@
+#![doc="
+BK Tree spelling corrector.
+
+See the `bktree` module for how the tree is built and searched, and the
+`metric` module for the distances it can be built with.
+"]
+
+pub mod bktree;
+pub mod metric;
+
+pub use crate::bktree::{BKTree, Node};
+pub use crate::metric::Metric;
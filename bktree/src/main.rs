@@ -1,4 +1,4 @@
-#[doc="
+#![doc="
 Input:
 * Constructs a BK Tree using the words in the given training file. Words in the
   training file are handled based on the rules specified in the input module.
@@ -9,6 +9,10 @@ Input:
   word with the highest frequency in the training file is chosen. If no words
   are found within a Levenshtein distance of 2, the program is unable to make a
   suggestion.
+* `--max-distance D` changes the search radius: a number of edits, `adaptive`,
+  or a percentage of the word's length, e.g. `--max-distance 25%` allows one
+  edit for words of up to 7 letters and two for words of 8 to 11 letters. Ties
+  are still broken by frequency.
 
 Output:
 * The program prints each word from stdin on a separate line.
@@ -36,42 +40,58 @@ edit distance 1 and world is more frequent. On the other hand, the BK tree
 corrector will suggest 'word' over 'world' because the Levenshtein distance
 between 'wordl' and 'word' is 1 but the Levenshtein distance between 'wordl'
 and 'world' is 2.
+
+Pass `--metric damerau` to count a transposition as a single edit instead,
+as the Norvig corrector does, so 'world' is suggested for 'wordl'. Words are
+reported with the restricted Damerau-Levenshtein distance, though the tree is
+built with the unrestricted one so that no word within the limit is missed
+(see the `metric` module). The default is `--metric levenshtein`.
+
+Distances are counted in Unicode chars, so words like 'café' and 'naïve' are
+handled. When built with the `graphemes` feature, `--metric
+levenshtein-graphemes` and `--metric damerau-graphemes` count grapheme
+clusters instead, so a letter followed by combining accents is one letter.
 "]
 
-use std::{env, fs};
+use std::env;
 use std::collections::HashMap;
 use std::io::stdin;
 
-mod bktree;
-mod input;
+use bktree::{BKTree, Metric, Node};
+use bktree::metric::{DamerauLevenshtein, Levenshtein};
+use spellcore::cli::{self, Args};
+use spellcore::distance::MaxDistance;
+use spellcore::input::Document;
 
 fn main() {
-    let args: Vec<_> = env::args().collect();
-    if args.len() != 2 {
-        panic!("Pass in one argument--the name of the training file");
-    }
-    let f = fs::File::open(&args[1]).expect("Error opening the training file");
-
-    let v: Vec<String> = input::read_input(f);
-    let root = bktree::Node::new(v[0].to_string(), 0, HashMap::new());
-    let mut bk = bktree::BKTree::new(root);
+    let value_flags = [cli::VALUE_FLAGS, &["--metric"]].concat();
+    let args = Args::parse(env::args().skip(1), &value_flags, cli::SWITCH_FLAGS)
+        .unwrap_or_else(|e| panic!("{}", e));
+    let metric: Box<dyn Metric<String>> = match args.value::<String>("--metric") {
+        Ok(None) => Box::new(Levenshtein),
+        Ok(Some(ref m)) if m == "levenshtein" => Box::new(Levenshtein),
+        Ok(Some(ref m)) if m == "damerau" => Box::new(DamerauLevenshtein),
+        #[cfg(feature = "graphemes")]
+        Ok(Some(ref m)) if m == "levenshtein-graphemes" => Box::new(bktree::metric::GraphemeLevenshtein),
+        #[cfg(feature = "graphemes")]
+        Ok(Some(ref m)) if m == "damerau-graphemes" => Box::new(bktree::metric::GraphemeDamerauLevenshtein),
+        _ => panic!("--metric must be levenshtein or damerau"),
+    };
+    let max_distance: Option<MaxDistance> = args.value("--max-distance")
+        .unwrap_or_else(|e| panic!("{}", e));
+    let training = cli::Training::read(&args);
+    let v: Vec<String> = training.words();
+    let root = Node::new(v[0].to_string(), 0, HashMap::new());
+    let mut bk = BKTree::with_metric(root, metric);
     for w in &v {
         bk.add(w);
     }
-
-    let input = input::read_input(stdin());
-    
-    for word in &input {
-        let best_suggestion = bk.search(word, &2);
-        if best_suggestion == "-" {
-            // No suggestions found
-            println!("{}, -", word);
-        } else if best_suggestion.len() == 0 {
-            // Word spelled correctly
-            println!("{}", word);
-        } else {
-            // Best suggestion
-            println!("{}, {}", word, best_suggestion);
-        }
+    if let Some(max_distance) = max_distance {
+        bk.set_max_distance(max_distance);
     }
+
+    let document = Document::from_reader(stdin())
+        .unwrap_or_else(|e| panic!("Error reading stdin: {}", e));
+    cli::print_document(&bk, &training, &document, &args);
 }
+
@@ -0,0 +1,295 @@
+#![doc="
+Metric
+A metric measures the distance between two items stored in a BK tree. The
+search only returns every item within the requested distance if the metric
+satisfies the triangle inequality, d(a, c) <= d(a, b) + d(b, c).
+
+* `Levenshtein`: insertions, deletions and substitutions on words.
+* `DamerauLevenshtein`: like `Levenshtein`, but a transposition of two adjacent
+  letters is a single edit. The restricted (optimal string alignment) distance
+  used by the Norvig corrector does not satisfy the triangle inequality, e.g.
+  `ac` is 1 from `ca` and 1 from `abc`, but `ca` is 3 from `abc`. So the tree
+  is weighted with the unrestricted distance, which does, and the words found
+  are reported with the restricted distance.
+* `GraphemeLevenshtein` and `GraphemeDamerauLevenshtein` (with the `graphemes`
+  feature): the same distances counted in grapheme clusters instead of chars.
+* `WeightedLevenshtein`: Levenshtein distance with a cost per kind of edit.
+  It is only symmetric, and so only a metric, when inserts and deletes cost
+  the same.
+* `Hamming`: the number of differing bits between two 64-bit hashes, such as
+  perceptual image hashes.
+* `JaroWinkler`: one minus the Jaro-Winkler similarity of two names, scaled to
+  an integer. It does not satisfy the triangle inequality, so a search can
+  miss some items within the requested distance.
+"]
+
+use std::cmp::{max, min};
+
+use spellcore::distance::{damerau_levenshtein_distance_within, levenshtein_distance,
+                          levenshtein_distance_within,
+                          unrestricted_damerau_levenshtein_distance};
+
+pub trait Metric<T> {
+    fn distance(&self, a: &T, b: &T) -> usize;
+
+    // The distance if it is at most limit, otherwise None. Metrics that can
+    // stop early once the limit is exceeded should override this.
+    fn distance_within(&self, a: &T, b: &T, limit: usize) -> Option<usize> {
+        let d = self.distance(a, b);
+        if d <= limit {
+            Some(d)
+        } else {
+            None
+        }
+    }
+
+    // The distance reported for an item the tree found dist from b, within
+    // limit, or None if it isn't within limit after all. Metrics that weight
+    // the tree with a different distance than they report override this.
+    fn reported(&self, _a: &T, _b: &T, dist: usize, _limit: usize) -> Option<usize> {
+        Some(dist)
+    }
+}
+
+impl<T, M: Metric<T> + ?Sized> Metric<T> for Box<M> {
+    fn distance(&self, a: &T, b: &T) -> usize {
+        (**self).distance(a, b)
+    }
+
+    fn distance_within(&self, a: &T, b: &T, limit: usize) -> Option<usize> {
+        (**self).distance_within(a, b, limit)
+    }
+
+    fn reported(&self, a: &T, b: &T, dist: usize, limit: usize) -> Option<usize> {
+        (**self).reported(a, b, dist, limit)
+    }
+}
+
+pub struct Levenshtein;
+
+impl Metric<String> for Levenshtein {
+    fn distance(&self, a: &String, b: &String) -> usize {
+        levenshtein_distance(a, b)
+    }
+
+    fn distance_within(&self, a: &String, b: &String, limit: usize) -> Option<usize> {
+        levenshtein_distance_within(a, b, limit)
+    }
+}
+
+pub struct DamerauLevenshtein;
+
+impl Metric<String> for DamerauLevenshtein {
+    fn distance(&self, a: &String, b: &String) -> usize {
+        unrestricted_damerau_levenshtein_distance(a, b)
+    }
+
+    fn distance_within(&self, a: &String, b: &String, limit: usize) -> Option<usize> {
+        // The distance is at least the difference in length
+        if a.chars().count().abs_diff(b.chars().count()) > limit {
+            return None;
+        }
+        Some(self.distance(a, b)).filter(|&d| d <= limit)
+    }
+
+    fn reported(&self, a: &String, b: &String, _: usize, limit: usize) -> Option<usize> {
+        damerau_levenshtein_distance_within(a, b, limit)
+    }
+}
+
+#[cfg(feature = "graphemes")]
+pub struct GraphemeLevenshtein;
+
+#[cfg(feature = "graphemes")]
+impl Metric<String> for GraphemeLevenshtein {
+    fn distance(&self, a: &String, b: &String) -> usize {
+        spellcore::distance::grapheme_levenshtein_distance(a, b)
+    }
+}
+
+#[cfg(feature = "graphemes")]
+pub struct GraphemeDamerauLevenshtein;
+
+#[cfg(feature = "graphemes")]
+impl Metric<String> for GraphemeDamerauLevenshtein {
+    fn distance(&self, a: &String, b: &String) -> usize {
+        spellcore::distance::grapheme_unrestricted_damerau_levenshtein_distance(a, b)
+    }
+
+    fn reported(&self, a: &String, b: &String, _: usize, limit: usize) -> Option<usize> {
+        Some(spellcore::distance::grapheme_damerau_levenshtein_distance(a, b))
+            .filter(|&d| d <= limit)
+    }
+}
+
+pub struct WeightedLevenshtein {
+    pub insert: usize,
+    pub delete: usize,
+    pub substitute: usize,
+}
+
+impl Metric<String> for WeightedLevenshtein {
+    fn distance(&self, a: &String, b: &String) -> usize {
+        let a: Vec<char> = a.chars().collect();
+        let b: Vec<char> = b.chars().collect();
+
+        // prev[j] is the cost of turning a[..i-1] into b[..j]
+        let mut prev: Vec<usize> = (0..(b.len()+1)).map(|j| j * self.insert).collect();
+        let mut cur = vec![0; b.len()+1];
+        for i in 1..(a.len()+1) {
+            cur[0] = i * self.delete;
+            for j in 1..(b.len()+1) {
+                let substitute = if a[i-1] == b[j-1] { 0 } else { self.substitute };
+                cur[j] = min(min(prev[j] + self.delete, cur[j-1] + self.insert),
+                             prev[j-1] + substitute);
+            }
+            std::mem::swap(&mut prev, &mut cur);
+        }
+        prev[b.len()]
+    }
+}
+
+pub struct Hamming;
+
+impl Metric<u64> for Hamming {
+    fn distance(&self, a: &u64, b: &u64) -> usize {
+        (a ^ b).count_ones() as usize
+    }
+}
+
+pub struct JaroWinkler {
+    // Distance between two strings that have nothing in common
+    pub scale: usize,
+}
+
+impl Default for JaroWinkler {
+    fn default() -> JaroWinkler {
+        JaroWinkler { scale: 100 }
+    }
+}
+
+impl Metric<String> for JaroWinkler {
+    fn distance(&self, a: &String, b: &String) -> usize {
+        ((1.0 - jaro_winkler_similarity(a, b)) * self.scale as f64).round() as usize
+    }
+}
+
+pub fn jaro_winkler_similarity(s1: &str, s2: &str) -> f64 {
+    let a: Vec<char> = s1.chars().collect();
+    let b: Vec<char> = s2.chars().collect();
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    // Characters match if they are equal and not too far apart
+    let window = (max(a.len(), b.len()) / 2).saturating_sub(1);
+    let mut a_matched = vec![false; a.len()];
+    let mut b_matched = vec![false; b.len()];
+    let mut matches = 0;
+    for i in 0..a.len() {
+        let lo = i.saturating_sub(window);
+        let hi = min(i + window + 1, b.len());
+        for j in lo..hi {
+            if !b_matched[j] && a[i] == b[j] {
+                a_matched[i] = true;
+                b_matched[j] = true;
+                matches += 1;
+                break;
+            }
+        }
+    }
+    if matches == 0 {
+        return 0.0;
+    }
+
+    // Half the number of matched characters that are out of order
+    let a_seq = a.iter().zip(&a_matched).filter(|p| *p.1).map(|p| p.0);
+    let b_seq = b.iter().zip(&b_matched).filter(|p| *p.1).map(|p| p.0);
+    let transpositions = a_seq.zip(b_seq).filter(|&(x, y)| x != y).count() / 2;
+
+    let m = matches as f64;
+    let jaro = (m / a.len() as f64 + m / b.len() as f64
+                + (m - transpositions as f64) / m) / 3.0;
+
+    // Boost strings sharing a prefix of up to 4 characters
+    let prefix = a.iter().zip(&b).take(4).take_while(|&(x, y)| x == y).count();
+    jaro + prefix as f64 * 0.1 * (1.0 - jaro)
+}
+
+#[cfg(test)]
+mod metric_tests {
+
+    use super::{jaro_winkler_similarity, DamerauLevenshtein, Hamming, JaroWinkler,
+                Levenshtein, Metric, WeightedLevenshtein};
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(Levenshtein.distance(&"wordl".to_string(), &"world".to_string()), 2);
+    }
+
+    #[test]
+    fn test_damerau_levenshtein() {
+        assert_eq!(DamerauLevenshtein.distance(&"wordl".to_string(), &"world".to_string()), 1);
+
+        // The tree is weighted with the unrestricted distance, but the
+        // restricted one is reported
+        let (ca, abc) = ("ca".to_string(), "abc".to_string());
+        assert_eq!(DamerauLevenshtein.distance(&ca, &abc), 2);
+        assert_eq!(DamerauLevenshtein.reported(&ca, &abc, 2, 3), Some(3));
+        assert_eq!(DamerauLevenshtein.reported(&ca, &abc, 2, 2), None);
+    }
+
+    #[test]
+    fn test_weighted_levenshtein() {
+        let m = WeightedLevenshtein { insert: 2, delete: 2, substitute: 3 };
+        assert_eq!(m.distance(&"cat".to_string(), &"hat".to_string()), 3);
+        assert_eq!(m.distance(&"cat".to_string(), &"cats".to_string()), 2);
+        assert_eq!(m.distance(&"cats".to_string(), &"cat".to_string()), 2);
+
+        // Two deletes are cheaper than two substitutions here
+        let m = WeightedLevenshtein { insert: 1, delete: 1, substitute: 5 };
+        assert_eq!(m.distance(&"ab".to_string(), &"ba".to_string()), 2);
+    }
+
+    #[test]
+    fn test_hamming() {
+        assert_eq!(Hamming.distance(&0b1011, &0b0010), 2);
+        assert_eq!(Hamming.distance(&0, &u64::MAX), 64);
+    }
+
+    #[test]
+    fn test_jaro_winkler_similarity() {
+        assert!((jaro_winkler_similarity("martha", "marhta") - 0.9611).abs() < 0.0001);
+        assert!((jaro_winkler_similarity("dwayne", "duane") - 0.84).abs() < 0.0001);
+        assert!((jaro_winkler_similarity("dixon", "dicksonx") - 0.8133).abs() < 0.0001);
+        assert_eq!(jaro_winkler_similarity("abc", "xyz"), 0.0);
+        assert_eq!(jaro_winkler_similarity("abc", "abc"), 1.0);
+    }
+
+    #[test]
+    fn test_jaro_winkler() {
+        let m = JaroWinkler::default();
+        assert_eq!(m.distance(&"martha".to_string(), &"marhta".to_string()), 4);
+        assert_eq!(m.distance(&"martha".to_string(), &"martha".to_string()), 0);
+    }
+
+    #[test]
+    fn test_distance_within() {
+        let (a, b) = ("hello".to_string(), "world".to_string());
+        assert_eq!(Levenshtein.distance_within(&a, &b, 4), Some(4));
+        assert_eq!(Levenshtein.distance_within(&a, &b, 3), None);
+        assert_eq!(DamerauLevenshtein.distance_within(&a, &b, 3), None);
+        assert_eq!(Hamming.distance_within(&0b1011, &0b0010, 1), None);
+        assert_eq!(Hamming.distance_within(&0b1011, &0b0010, 2), Some(2));
+    }
+
+    #[test]
+    fn test_boxed_metric() {
+        let m: Box<dyn Metric<String>> = Box::new(DamerauLevenshtein);
+        assert_eq!(m.distance(&"fera".to_string(), &"fear".to_string()), 1);
+    }
+
+}
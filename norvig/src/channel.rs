@@ -0,0 +1,296 @@
+#![doc="
+Noisy channel
+Ranks the corrections w of a misspelled word x by P(w)·P(x|w) instead of by
+frequency alone. P(w) is proportional to the frequency of w in the training
+file. P(x|w), the chance of typing x when meaning w, is the product of the
+probabilities of the edits that turn w into x, each taken from a confusion
+matrix per kind of edit:
+
+* insert: typing a letter after the intended letter (or at the start of the
+  word)
+* delete: leaving out a letter after the intended letter
+* substitute: typing one letter instead of another
+* transpose: typing two adjacent letters the wrong way round
+
+The matrices are trained from a file of misspellings and their corrections,
+one pair per line separated by whitespace. Each count is smoothed by adding
+one, so edits never seen in training are unlikely but still possible.
+
+ex.
+  speling spelling
+  teh the
+"]
+
+use std::collections::HashMap;
+use std::io::Read;
+
+use spellcore::Suggestion;
+
+// Stands in for the letter before the first letter of a word
+const START: char = '#';
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Edit {
+    // Typed the letter after the intended letter
+    Insert { prev: char, typed: char },
+
+    // Left out the letter after the intended letter
+    Delete { prev: char, intended: char },
+
+    Substitute { typed: char, intended: char },
+
+    // Typed the intended letters first then second the wrong way round
+    Transpose { first: char, second: char },
+}
+
+#[derive(Default)]
+pub struct ErrorModel {
+    inserts: HashMap<(char, char), usize>,
+    deletes: HashMap<(char, char), usize>,
+    substitutes: HashMap<(char, char), usize>,
+    transposes: HashMap<(char, char), usize>,
+
+    // How often each letter and pair of adjacent letters appears in the
+    // intended words, to turn the counts of edits into probabilities
+    letters: HashMap<char, usize>,
+    pairs: HashMap<(char, char), usize>,
+}
+
+impl ErrorModel {
+
+    pub fn new() -> ErrorModel {
+        ErrorModel::default()
+    }
+
+    // Trains a model from (misspelling, correction) pairs
+    pub fn from_pairs(pairs: &[(String, String)]) -> ErrorModel {
+        let mut model = ErrorModel::new();
+        for (typed, intended) in pairs {
+            model.add_pair(typed, intended);
+        }
+        model
+    }
+
+    pub fn add_pair(&mut self, typed: &str, intended: &str) {
+        let intended_chars: Vec<char> = intended.chars().collect();
+        let mut prev = START;
+        *self.letters.entry(START).or_insert(0) += 1;
+        for &c in &intended_chars {
+            *self.letters.entry(c).or_insert(0) += 1;
+            *self.pairs.entry((prev, c)).or_insert(0) += 1;
+            prev = c;
+        }
+
+        let typed_chars: Vec<char> = typed.chars().collect();
+        for edit in alignment(&typed_chars, &intended_chars) {
+            let (matrix, key) = match edit {
+                Edit::Insert { prev, typed } => (&mut self.inserts, (prev, typed)),
+                Edit::Delete { prev, intended } => (&mut self.deletes, (prev, intended)),
+                Edit::Substitute { typed, intended } => (&mut self.substitutes, (typed, intended)),
+                Edit::Transpose { first, second } => (&mut self.transposes, (first, second)),
+            };
+            *matrix.entry(key).or_insert(0) += 1;
+        }
+    }
+
+    // P(typed|intended), the chance of typing typed when meaning intended
+    pub fn probability(&self, typed: &str, intended: &str) -> f64 {
+        let typed: Vec<char> = typed.chars().collect();
+        let intended: Vec<char> = intended.chars().collect();
+        alignment(&typed, &intended).into_iter()
+            .map(|edit| self.edit_probability(edit))
+            .product()
+    }
+
+    // P(w)·P(x|w) up to a constant factor, for typed word x and suggestion w
+    pub fn score(&self, typed: &str, suggestion: &Suggestion) -> f64 {
+        suggestion.frequency as f64 * self.probability(typed, &suggestion.word)
+    }
+
+    // Sorts suggestions for typed from most to least likely, breaking ties
+    // alphabetically
+    pub fn rank(&self, typed: &str, suggestions: &mut [Suggestion]) {
+        let mut scored: Vec<(f64, Suggestion)> = suggestions.iter()
+            .map(|s| (self.score(typed, s), s.clone()))
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.word.cmp(&b.1.word)));
+        for (slot, (_, s)) in suggestions.iter_mut().zip(scored) {
+            *slot = s;
+        }
+    }
+
+    fn edit_probability(&self, edit: Edit) -> f64 {
+        let (count, total) = match edit {
+            Edit::Insert { prev, typed } =>
+                (get(&self.inserts, &(prev, typed)), get(&self.letters, &prev)),
+            Edit::Delete { prev, intended } =>
+                (get(&self.deletes, &(prev, intended)), get(&self.pairs, &(prev, intended))),
+            Edit::Substitute { typed, intended } =>
+                (get(&self.substitutes, &(typed, intended)), get(&self.letters, &intended)),
+            Edit::Transpose { first, second } =>
+                (get(&self.transposes, &(first, second)), get(&self.pairs, &(first, second))),
+        };
+
+        // Add one smoothing over every letter seen, plus the start of a word
+        let letters = self.letters.len().max(1) + 1;
+        (count + 1) as f64 / (total + letters) as f64
+    }
+
+}
+
+fn get<K: std::hash::Hash + Eq>(counts: &HashMap<K, usize>, key: &K) -> usize {
+    counts.get(key).cloned().unwrap_or(0)
+}
+
+// Reads misspelling and correction pairs, one per line. Blank lines are
+// skipped and words are lowercased to match the training file.
+pub fn read_pairs<R: Read>(mut reader: R) -> Result<Vec<(String, String)>, String> {
+    let mut s = String::new();
+    reader.read_to_string(&mut s).map_err(|e| e.to_string())?;
+    let mut pairs = Vec::new();
+    for (i, line) in s.lines().enumerate() {
+        let words: Vec<String> = line.split_whitespace().map(|w| w.to_lowercase()).collect();
+        match words.len() {
+            0 => {}
+            2 => pairs.push((words[0].clone(), words[1].clone())),
+            _ => return Err(format!("Line {} should be a misspelling and its correction", i + 1)),
+        }
+    }
+    Ok(pairs)
+}
+
+// The edits of one cheapest way to turn intended into typed, counting a
+// transposition of adjacent letters as a single edit
+fn alignment(typed: &[char], intended: &[char]) -> Vec<Edit> {
+    let (m, n) = (intended.len(), typed.len());
+
+    // d[i][j] is the distance between intended[..i] and typed[..j]
+    let mut d = vec![vec![0; n+1]; m+1];
+    d[0] = (0..(n+1)).collect();
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for i in 1..(m+1) {
+        for j in 1..(n+1) {
+            let cost = if intended[i-1] == typed[j-1] { 0 } else { 1 };
+            d[i][j] = (d[i-1][j] + 1).min(d[i][j-1] + 1).min(d[i-1][j-1] + cost);
+            if i > 1 && j > 1 && intended[i-1] == typed[j-2] && intended[i-2] == typed[j-1] {
+                d[i][j] = d[i][j].min(d[i-2][j-2] + 1);
+            }
+        }
+    }
+
+    // Walk back from the end, preferring matches and substitutions
+    let prev = |i: usize| if i > 0 { intended[i-1] } else { START };
+    let mut edits = Vec::new();
+    let (mut i, mut j) = (m, n);
+    while i > 0 || j > 0 {
+        if i > 0 && j > 0 && intended[i-1] == typed[j-1] && d[i][j] == d[i-1][j-1] {
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && j > 0 && d[i][j] == d[i-1][j-1] + 1 {
+            edits.push(Edit::Substitute { typed: typed[j-1], intended: intended[i-1] });
+            i -= 1;
+            j -= 1;
+        } else if i > 1 && j > 1 && intended[i-1] == typed[j-2] && intended[i-2] == typed[j-1]
+                  && d[i][j] == d[i-2][j-2] + 1 {
+            edits.push(Edit::Transpose { first: intended[i-2], second: intended[i-1] });
+            i -= 2;
+            j -= 2;
+        } else if i > 0 && d[i][j] == d[i-1][j] + 1 {
+            edits.push(Edit::Delete { prev: prev(i-1), intended: intended[i-1] });
+            i -= 1;
+        } else {
+            edits.push(Edit::Insert { prev: prev(i), typed: typed[j-1] });
+            j -= 1;
+        }
+    }
+    edits.reverse();
+    edits
+}
+
+#[cfg(test)]
+mod channel_tests {
+
+    use spellcore::Suggestion;
+    use super::{alignment, read_pairs, Edit, ErrorModel, START};
+
+    fn align(typed: &str, intended: &str) -> Vec<Edit> {
+        let typed: Vec<char> = typed.chars().collect();
+        let intended: Vec<char> = intended.chars().collect();
+        alignment(&typed, &intended)
+    }
+
+    #[test]
+    fn aligns_single_edits() {
+        assert_eq!(align("the", "the"), vec![]);
+        assert_eq!(align("thhe", "the"), vec![Edit::Insert { prev: 't', typed: 'h' }]);
+        assert_eq!(align("xthe", "the"), vec![Edit::Insert { prev: START, typed: 'x' }]);
+        assert_eq!(align("te", "the"), vec![Edit::Delete { prev: 't', intended: 'h' }]);
+        assert_eq!(align("he", "the"), vec![Edit::Delete { prev: START, intended: 't' }]);
+        assert_eq!(align("thw", "the"), vec![Edit::Substitute { typed: 'w', intended: 'e' }]);
+        assert_eq!(align("teh", "the"), vec![Edit::Transpose { first: 'h', second: 'e' }]);
+    }
+
+    #[test]
+    fn aligns_several_edits() {
+        assert_eq!(align("speling", "spelling").len(), 1);
+        assert_eq!(align("acress", "actress"), vec![Edit::Delete { prev: 'c', intended: 't' }]);
+        assert_eq!(align("nñio", "niño"), vec![Edit::Transpose { first: 'i', second: 'ñ' }]);
+        assert_eq!(align("nino", "niño"), vec![Edit::Substitute { typed: 'n', intended: 'ñ' }]);
+        assert_eq!(align("thx", "tea").len(), 2);
+    }
+
+    #[test]
+    fn untrained_edits_are_equally_likely() {
+        let model = ErrorModel::new();
+        assert_eq!(model.probability("teh", "the"), model.probability("thw", "the"));
+        assert_eq!(model.probability("the", "the"), 1.0);
+    }
+
+    #[test]
+    fn trained_edits_are_more_likely() {
+        let pairs = vec![("teh".to_string(), "the".to_string()),
+                         ("hte".to_string(), "the".to_string()),
+                         ("adn".to_string(), "and".to_string())];
+        let model = ErrorModel::from_pairs(&pairs);
+        assert!(model.probability("teh", "the") > model.probability("thw", "the"));
+        assert!(model.probability("hte", "the") > model.probability("tje", "the"));
+        assert!(model.probability("teh", "the") > model.probability("teh", "tea"));
+        assert!(model.probability("teh", "the") <= 1.0);
+    }
+
+    #[test]
+    fn two_edits_are_less_likely_than_one() {
+        let model = ErrorModel::new();
+        assert!(model.probability("txx", "the") < model.probability("thx", "the"));
+    }
+
+    #[test]
+    fn frequent_words_beat_closer_words() {
+        let model = ErrorModel::new();
+        let mut v = vec![Suggestion::new("bat", 1, 1), Suggestion::new("boot", 2, 10)];
+        model.rank("bxt", &mut v);
+        assert_eq!(v[0].word, "boot");
+    }
+
+    #[test]
+    fn likely_edits_beat_equally_frequent_words() {
+        let pairs = vec![("teh".to_string(), "the".to_string()),
+                         ("hte".to_string(), "the".to_string())];
+        let model = ErrorModel::from_pairs(&pairs);
+        let mut v = vec![Suggestion::new("tea", 1, 10), Suggestion::new("the", 1, 10)];
+        model.rank("teh", &mut v);
+        assert_eq!(v[0].word, "the");
+    }
+
+    #[test]
+    fn reads_pairs() {
+        let s = "teh the\n\nAdn  and\n";
+        assert_eq!(read_pairs(s.as_bytes()),
+                   Ok(vec![("teh".to_string(), "the".to_string()),
+                           ("adn".to_string(), "and".to_string())]));
+        assert!(read_pairs("teh\n".as_bytes()).is_err());
+    }
+
+}
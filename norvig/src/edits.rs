@@ -1,116 +1,325 @@
-#[doc="
+#![doc="
 Provides functions to
   * Compute the possible edits to a word, where an edit can be a deletion
     (remove one letter), a transposition (swap adjacent letters), an alteration
     (change one letter to another) or an insertion (add a letter).
+    Alterations and insertions use the letters of a given alphabet, which is
+    usually every letter seen in the training file (see `alphabet_from`).
   * Check if word is known (ie. found in training file)
   * Suggest best correction to misspelled words
 "]
 
-use input;
+use std::collections::HashSet;
 
-const ALPHABET: &'static str = "abcdefghijklmnopqrstuvwxyz";
+use spellcore::{input, Correction, Suggestion};
+use spellcore::distance::{damerau_levenshtein_distance_within, MaxDistance};
 
-fn edits1(word: &str) -> Vec<String> {
-    let mut v = Vec::new();
-    let word_len = word.len();
-    let mut splits = Vec::new();
+pub const ALPHABET: &str = "abcdefghijklmnopqrstuvwxyz";
 
-    for i in 0..(word_len+1) {
-        splits.push((&word[..i], &word[i..]));
+// Every letter that appears in a word of the training file, in order
+pub fn alphabet_from(word_library: &input::CountTable) -> Vec<char> {
+    let mut letters: Vec<char> = word_library.keys().flat_map(|w| w.chars()).collect();
+    letters.sort();
+    letters.dedup();
+    letters
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Edit {
+    Delete,
+    Transpose,
+    Replace,
+    Insert,
+}
+
+// Lazily generates each distinct edit of a word once: deletes, then transposes,
+// then replaces, then inserts
+struct Edits1<'a> {
+    word: String,
+    alphabet: &'a [char],
+
+    // Byte offsets of the splits between letters, including both ends, so
+    // multibyte letters stay whole
+    splits: Vec<usize>,
+
+    // Position of the next edit; edit is None once every edit is generated
+    edit: Option<Edit>,
+    split: usize,
+    letter: usize,
+
+    seen: HashSet<String>,
+}
+
+impl<'a> Edits1<'a> {
+
+    fn new(word: String, alphabet: &'a [char]) -> Edits1<'a> {
+        let mut splits: Vec<usize> = word.char_indices().map(|(i, _)| i).collect();
+        splits.push(word.len());
+        Edits1 {
+            word,
+            alphabet,
+            splits,
+            edit: Some(Edit::Delete),
+            split: 0,
+            letter: 0,
+            seen: HashSet::new(),
+        }
     }
 
-    //Deletes
-    for t in &splits {
-        if t.1.len() > 0 {
-            let new_edit = t.0.to_string() + &t.1[1..];
-            if !v.contains(&new_edit) {
-                v.push(new_edit);
+    // The next edit in order, which may repeat an earlier one
+    fn next_edit(&mut self) -> Option<String> {
+        loop {
+            let edit = self.edit?;
+            if self.split == self.splits.len() {
+                self.edit = match edit {
+                    Edit::Delete => Some(Edit::Transpose),
+                    Edit::Transpose => Some(Edit::Replace),
+                    Edit::Replace => Some(Edit::Insert),
+                    Edit::Insert => None,
+                };
+                self.split = 0;
+                continue;
+            }
+
+            let (left, right) = self.word.split_at(self.splits[self.split]);
+            let mut rest = right.chars();
+            let first = rest.next();
+            match edit {
+                Edit::Delete => {
+                    self.split += 1;
+                    if first.is_some() {
+                        return Some(left.to_string() + rest.as_str());
+                    }
+                }
+                Edit::Transpose => {
+                    self.split += 1;
+                    if let (Some(first), Some(second)) = (first, rest.next()) {
+                        let mut new_edit = left.to_string();
+                        new_edit.push(second);
+                        new_edit.push(first);
+                        new_edit.push_str(rest.as_str());
+                        return Some(new_edit);
+                    }
+                }
+                Edit::Replace | Edit::Insert => {
+                    if self.letter == self.alphabet.len()
+                        || (edit == Edit::Replace && first.is_none()) {
+                        self.split += 1;
+                        self.letter = 0;
+                        continue;
+                    }
+                    let mut new_edit = left.to_string();
+                    new_edit.push(self.alphabet[self.letter]);
+                    new_edit.push_str(if edit == Edit::Replace { rest.as_str() } else { right });
+                    self.letter += 1;
+                    return Some(new_edit);
+                }
             }
         }
     }
 
-    //Transposes
-    for t in &splits {
-        if t.1.len() > 1 {
-            let new_edit = t.0.to_string() + &t.1[1..2] + &t.1[0..1] + &t.1[2..];
-            if !v.contains(&new_edit) {
-                v.push(new_edit);
+}
+
+impl<'a> Iterator for Edits1<'a> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        loop {
+            let new_edit = self.next_edit()?;
+            if self.seen.insert(new_edit.clone()) {
+                return Some(new_edit);
             }
         }
     }
+}
+
+// Lazily generates each distinct edit of each edit of a word once
+struct Edits2<'a> {
+    e1s: Edits1<'a>,
+    e2s: Option<Edits1<'a>>,
+    seen: HashSet<String>,
+}
+
+impl<'a> Iterator for Edits2<'a> {
+    type Item = String;
 
-    //Replaces
-    for t in &splits {
-        if t.1.len() > 0 {
-            for i in 0..ALPHABET.len() {
-                let new_edit = t.0.to_string() + &ALPHABET[i..(i+1)] + &t.1[1..];
-                if !v.contains(&new_edit) {
-                    v.push(new_edit);
+    fn next(&mut self) -> Option<String> {
+        loop {
+            match self.e2s.as_mut().and_then(|e2s| e2s.next_edit()) {
+                Some(new_edit) => {
+                    if self.seen.insert(new_edit.clone()) {
+                        return Some(new_edit);
+                    }
+                }
+                None => {
+                    let e1 = self.e1s.next()?;
+                    self.e2s = Some(Edits1::new(e1, self.e1s.alphabet));
                 }
             }
         }
     }
+}
 
-    //Inserts
-    for t in &splits {
-        for i in 0..ALPHABET.len() {
-            let new_edit = t.0.to_string() + &ALPHABET[i..(i+1)] + &t.1[..];
-            if !v.contains(&new_edit) {
-                v.push(new_edit);
-            }
-        }
+fn edits1<'a>(word: &str, alphabet: &'a [char]) -> Edits1<'a> {
+    Edits1::new(word.to_string(), alphabet)
+}
+
+fn edits2<'a>(word: &str, alphabet: &'a [char]) -> Edits2<'a> {
+    Edits2 {
+        e1s: edits1(word, alphabet),
+        e2s: None,
+        seen: HashSet::new(),
     }
-    v
 }
 
-fn edits2(e1s: &Vec<String>) -> Vec<String> {
-    let mut v = Vec::new();
-    for e1 in e1s {
-        for e2 in edits1(&e1) {
-            v.push(e2);
-        }
+// Looks each word up as it is generated
+fn known<'a, I>(words: I, word_library: &'a input::CountTable) -> impl Iterator<Item = (String, usize)> + 'a
+    where I: Iterator<Item = String> + 'a
+{
+    words.filter_map(move |word| word_library.get(&word).map(|&freq| (word, freq)))
+}
+
+// Known words exactly distance edits from word. The first two levels generate
+// every edit and look them up. Beyond that there are far too many edits to
+// generate, so instead every known word is measured against word with the
+// restricted Damerau-Levenshtein distance, giving up on each as soon as it is
+// further than distance. Only reached when nothing closer was found, so words
+// already found at a lower level aren't repeated.
+fn known_at<'a>(word: &'a str,
+                word_library: &'a input::CountTable,
+                alphabet: &'a [char],
+                distance: usize)
+                -> Box<dyn Iterator<Item = (String, usize)> + 'a> {
+    match distance {
+        0 => Box::new(word_library.get(word).map(|&freq| (word.to_string(), freq)).into_iter()),
+        1 => Box::new(known(edits1(word, alphabet), word_library)),
+        2 => Box::new(known(edits2(word, alphabet), word_library)),
+        _ => Box::new(word_library.iter()
+            .filter(move |&(w, _)| {
+                damerau_levenshtein_distance_within(word, w, distance) == Some(distance)
+            })
+            .map(|(w, &freq)| (w.to_string(), freq))),
     }
-    v
 }
 
-fn known(words: &Vec<String>, word_library: &input::CountTable) -> Vec<(String, usize)> {
-    let mut v = Vec::new();
-    for word in words {
-        match word_library.get(word) {
-            Some(&freq) => v.push((word.to_string(), freq)),
-            None => continue
+// max_distance, capped where deeper levels can't find anything: no known word
+// is further from word than the longer of the two is long
+fn deepest(word: &str, word_library: &input::CountTable, max_distance: usize) -> usize {
+    if max_distance <= 2 {
+        return max_distance;
+    }
+    let longest = word_library.keys().map(|w| w.chars().count()).max().unwrap_or(0);
+    MaxDistance::Fixed(max_distance).within(word, longest)
+}
+
+// Known words closest to word, along with the edit distance they were found at
+pub fn candidates(word: &str,
+                  word_library: &input::CountTable,
+                  alphabet: &[char],
+                  max_distance: usize)
+                  -> (usize, Vec<(String, usize)>) {
+    let max_distance = deepest(word, word_library, max_distance);
+    for distance in 1..=max_distance {
+        let candidates: Vec<(String, usize)> =
+            known_at(word, word_library, alphabet, distance).collect();
+        if !candidates.is_empty() {
+            return (distance, candidates);
         }
     }
-    v
+    (max_distance, Vec::new())
 }
 
-pub fn correct(word: &str, word_library: &input::CountTable) -> String {
-    let e1s = edits1(word);
-    let mut candidates = known(&e1s, word_library);
-    if candidates.len() <= 0 {
-        candidates = known(&edits2(&e1s), word_library);
+pub fn correct(word: &str,
+               word_library: &input::CountTable,
+               alphabet: &[char],
+               max_distance: usize)
+               -> Correction {
+    if word_library.contains_key(word) {
+        return Correction::Known;
     }
-    let mut best_word: String = "-".to_string();
+    let (distance, candidates) = candidates(word, word_library, alphabet, max_distance);
+    let mut best_word: Option<&String> = None;
     let mut best_word_score: usize = 0;
     for pair in &candidates {
-        if  pair.1 > best_word_score {
-            best_word = pair.0.to_string();
+        // Break ties alphabetically so the choice doesn't depend on edit order
+        if  pair.1 > best_word_score
+            || (pair.1 == best_word_score && best_word.is_some_and(|w| pair.0 < *w)) {
+            best_word = Some(&pair.0);
             best_word_score = pair.1;
         }
     }
-    best_word
+    match best_word {
+        Some(w) => Correction::Suggested {
+            word: w.to_string(),
+            distance,
+            frequency: best_word_score,
+        },
+        None => Correction::NoSuggestion,
+    }
+}
+
+// Up to k known words within max_distance edits of word, best first. Each
+// level of edits is only searched if the closer levels don't yield k words.
+pub fn suggestions(word: &str,
+                   word_library: &input::CountTable,
+                   alphabet: &[char],
+                   max_distance: usize,
+                   k: usize)
+                   -> Vec<Suggestion> {
+    let mut v: Vec<Suggestion> = Vec::new();
+
+    // Edits can reproduce the word itself (eg. replacing a letter with itself)
+    // and edits2 reproduces the first level edits too
+    let mut found = HashSet::new();
+    for distance in 0..=deepest(word, word_library, max_distance) {
+        if v.len() >= k {
+            break;
+        }
+        for (w, freq) in known_at(word, word_library, alphabet, distance) {
+            if found.insert(w.to_string()) {
+                v.push(Suggestion::new(&w, distance, freq));
+            }
+        }
+    }
+
+    v.sort_by(Suggestion::rank);
+    v.truncate(k);
+    v
+}
+
+// Every known word within max_distance edits of word, including word itself,
+// at the distance it is first found
+pub fn within(word: &str,
+              word_library: &input::CountTable,
+              alphabet: &[char],
+              max_distance: usize)
+              -> Vec<Suggestion> {
+    let mut v: Vec<Suggestion> = Vec::new();
+    let mut found = HashSet::new();
+    for distance in 0..=deepest(word, word_library, max_distance) {
+        for (w, freq) in known_at(word, word_library, alphabet, distance) {
+            if found.insert(w.to_string()) {
+                v.push(Suggestion::new(&w, distance, freq));
+            }
+        }
+    }
+    v
 }
 
 #[cfg(test)]
 mod edits1_tests {
 
-    use super::edits1;
+    use std::collections::HashSet;
+    use super::{edits1, edits2, ALPHABET};
+
+    fn ascii() -> Vec<char> {
+        ALPHABET.chars().collect()
+    }
 
     #[test]
     fn edits1_for_a() {
         let word = "a";
-        let v = edits1(word);
+        let v: Vec<String> = edits1(word, &ascii()).collect();
         assert_eq!(78, v.len());
         let subset = vec!["b", "c", "d", "e", "f", "x", "y", "z", "ab", "ac",
                           "ad", "ae", "af", "ax", "ay", "az", "ab", "ac", "ad",
@@ -123,7 +332,7 @@ mod edits1_tests {
     #[test]
     fn edits1_for_e() {
         let word = "e";
-        let v = edits1(word);
+        let v: Vec<String> = edits1(word, &ascii()).collect();
         assert_eq!(78, v.len());
         let subset = vec!["b", "c", "d", "f", "x", "y", "z", "eb", "ec", "ed",
                           "ef", "ex", "ey", "ez", "eb", "ec", "ed", "ee", "ef",
@@ -136,7 +345,7 @@ mod edits1_tests {
     #[test]
     fn edits1_for_aaaaa() {
         let word = "aaaaa";
-        let v = edits1(word);
+        let v: Vec<String> = edits1(word, &ascii()).collect();
         assert_eq!(278, v.len());
         let subset = vec!["aaaa", "aaaaaa", "aaaab", "aacaa", "aaadaa",
                           "faaaaa", "axaaaa", "aaaaya", "azaaa"];
@@ -148,14 +357,14 @@ mod edits1_tests {
     #[test]
     fn edits1_for_abc() {
         let word = "abc";
-        let v = edits1(word);
+        let v: Vec<String> = edits1(word, &ascii()).collect();
         assert_eq!(182, v.len());
     }
 
     #[test]
     fn edits1_for_abcde() {
         let word = "abcde";
-        let v = edits1(word);
+        let v: Vec<String> = edits1(word, &ascii()).collect();
         assert_eq!(286, v.len());
         let subset = vec!["abcd", "abde", "cbcde", "abcze", "abcdef", "yabcde",
                           "bcde", "atcde", "bacde"];
@@ -167,7 +376,7 @@ mod edits1_tests {
     #[test]
     fn edits1_for_abcdefghijklmnopqrstuvwxyz() {
         let word = "abcdefghijklmnopqrstuvwxyz";
-        let v = edits1(word);
+        let v: Vec<String> = edits1(word, &ascii()).collect();
         assert_eq!(1378, v.len());
         let subset = vec!["abcdefhijklmnopqrstuvwxyz",
                           "abcdefghikjlmnopqrstuvwxyz",
@@ -181,7 +390,7 @@ mod edits1_tests {
     #[test]
     fn edits1_for_hello() {
         let word = "hello";
-        let v = edits1(word);
+        let v: Vec<String> = edits1(word, &ascii()).collect();
         assert_eq!(284, v.len());
         let subset = vec!["helloh", "yello", "hlelo", "helol", "ahello",
                           "helo", "jello"];
@@ -193,7 +402,7 @@ mod edits1_tests {
     #[test]
     fn edits1_for_something() {
         let word = "something";
-        let v = edits1(word);
+        let v: Vec<String> = edits1(word, &ascii()).collect();
         assert_eq!(494, v.len());
         let subset = vec!["somthing", "somdthing", "aomething", "somehting"];
         for word in subset {
@@ -201,10 +410,50 @@ mod edits1_tests {
         }
     }
 
+    #[test]
+    fn edits1_has_no_duplicates() {
+        let v: Vec<String> = edits1("aaaaa", &ascii()).collect();
+        let unique: HashSet<&String> = v.iter().collect();
+        assert_eq!(unique.len(), v.len());
+    }
+
+    #[test]
+    fn edits2_for_ab() {
+        let v: Vec<String> = edits2("ab", &ascii()).collect();
+        let unique: HashSet<&String> = v.iter().collect();
+        assert_eq!(unique.len(), v.len());
+        assert_eq!(7154, v.len());
+        let subset = vec!["ab", "", "ba", "b", "xab", "xaby", "axby", "xy", "bca"];
+        for word in subset {
+            assert!(v.contains(&word.to_string()));
+        }
+    }
+
+    #[test]
+    fn edits2_is_lazy() {
+        let alphabet = ascii();
+        let mut e2s = edits2("abcdefghijklmnopqrstuvwxyz", &alphabet);
+        assert_eq!(Some("cdefghijklmnopqrstuvwxyz".to_string()), e2s.next());
+        assert!(e2s.e1s.seen.len() < 5);
+    }
+
+    #[test]
+    fn edits1_for_nino() {
+        let word = "niño";
+        let alphabet: Vec<char> = "abcdefghijklmnñopqrstuvwxyz".chars().collect();
+        let v: Vec<String> = edits1(word, &alphabet).collect();
+        // 4 deletes, 3 transposes, 4 * 26 new replaces, 5 * 27 - 3 new inserts
+        assert_eq!(243, v.len());
+        let subset = vec!["nio", "inño", "niñó", "nino", "ninño", "niñoo", "ñiño"];
+        for word in subset {
+            assert_eq!(!word.contains('ó'), v.contains(&word.to_string()));
+        }
+    }
+
     #[test]
     fn edits1_for_anything() {
         let word = "anything";
-        let v = edits1(word);
+        let v: Vec<String> = edits1(word, &ascii()).collect();
         assert_eq!(442, v.len());
         let subset = vec!["anzthing", "anyting", "antyhing", "manything"];
         for word in subset {
@@ -217,13 +466,18 @@ mod edits1_tests {
 #[cfg(test)]
 mod correct_tests {
 
-    use input;
-    use super::correct;
+    use spellcore::{input, Correction};
+    use super::{alphabet_from, ALPHABET};
+
+    fn correct(word: &str, lib: &input::CountTable) -> Correction {
+        let alphabet: Vec<char> = ALPHABET.chars().collect();
+        super::correct(word, lib, &alphabet, 2)
+    }
 
     #[test]
     fn test_no_suggestions_found() {
         let lib = fixture();
-        assert_eq!(correct("asjkdghlaksjdghls", &lib), "-".to_string());
+        assert_eq!(correct("asjkdghlaksjdghls", &lib), Correction::NoSuggestion);
     }
 
     #[test]
@@ -231,13 +485,80 @@ mod correct_tests {
         // ho should suggest who over how 
         // since who has freq=2 while how has freq=1
         let lib = fixture();
-        assert_eq!(correct("ho", &lib), "who".to_string());
+        assert_eq!(correct("ho", &lib), suggested("who", 1, 2));
     }
 
     #[test]
     fn test_correct_suggestion() {
         let lib = fixture();
-        assert_eq!(correct("wherf", &lib), "where".to_string());
+        assert_eq!(correct("wherf", &lib), suggested("where", 1, 2));
+    }
+
+    #[test]
+    fn test_no_need_to_correct_word() {
+        let lib = fixture();
+        assert_eq!(correct("who", &lib), Correction::Known);
+    }
+
+    #[test]
+    fn test_reports_second_level_distance() {
+        let lib = fixture();
+        assert_eq!(correct("threx", &lib), suggested("three", 1, 3));
+        assert_eq!(correct("thrxx", &lib), suggested("three", 2, 3));
+    }
+
+    #[test]
+    fn test_breaks_ties_alphabetically() {
+        // hoe and how are both edit distance 1 with freq=1
+        let mut lib = fixture();
+        lib.insert("hoe".to_string(), 1);
+        lib.remove("who");
+        assert_eq!(correct("ho", &lib), suggested("hoe", 1, 1));
+    }
+
+    #[test]
+    fn test_alphabet_from_training_words() {
+        let mut lib = input::CountTable::new();
+        lib.insert("año".to_string(), 1);
+        lib.insert("straße".to_string(), 1);
+        assert_eq!(alphabet_from(&lib), vec!['a', 'e', 'o', 'r', 's', 't', 'ß', 'ñ']);
+    }
+
+    #[test]
+    fn test_corrects_with_letters_seen_in_training() {
+        let mut lib = input::CountTable::new();
+        lib.insert("año".to_string(), 3);
+        lib.insert("straße".to_string(), 1);
+        let alphabet = alphabet_from(&lib);
+        assert_eq!(super::correct("ano", &lib, &alphabet, 2), suggested("año", 1, 3));
+        assert_eq!(super::correct("strase", &lib, &alphabet, 2), suggested("straße", 1, 1));
+        assert_eq!(super::correct("aoñ", &lib, &alphabet, 2), suggested("año", 1, 3));
+    }
+
+    #[test]
+    fn test_max_distance() {
+        let lib = fixture();
+        let alphabet: Vec<char> = ALPHABET.chars().collect();
+        assert_eq!(super::correct("thrxx", &lib, &alphabet, 1), Correction::NoSuggestion);
+        assert_eq!(super::correct("thrxx", &lib, &alphabet, 2), suggested("three", 2, 3));
+        assert_eq!(super::correct("thxxx", &lib, &alphabet, 2), Correction::NoSuggestion);
+        assert_eq!(super::correct("thxxx", &lib, &alphabet, 3), suggested("three", 3, 3));
+        assert_eq!(super::correct("thxxx", &lib, &alphabet, 4), suggested("three", 3, 3));
+        assert_eq!(super::correct("ho", &lib, &alphabet, 0), Correction::NoSuggestion);
+        assert_eq!(super::correct("thxxx", &lib, &alphabet, usize::MAX), suggested("three", 3, 3));
+        assert_eq!(super::suggestions("ho", &lib, &alphabet, usize::MAX, 100).len(), lib.len());
+        assert_eq!(super::within("ho", &lib, &alphabet, usize::MAX).len(), lib.len());
+    }
+
+    #[test]
+    fn test_third_level_counts_transpositions() {
+        let lib = fixture();
+        let alphabet: Vec<char> = ALPHABET.chars().collect();
+        assert_eq!(super::correct("htrexx", &lib, &alphabet, 3), suggested("three", 3, 3));
+    }
+
+    fn suggested(word: &str, distance: usize, frequency: usize) -> Correction {
+        Correction::Suggested { word: word.to_string(), distance, frequency }
     }
 
     fn fixture() -> input::CountTable {
@@ -257,4 +578,68 @@ mod correct_tests {
         h
     }
 
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod suggestions_tests {
+
+    use spellcore::{input, Suggestion};
+    use super::ALPHABET;
+
+    fn suggestions(word: &str, lib: &input::CountTable, k: usize) -> Vec<Suggestion> {
+        let alphabet: Vec<char> = ALPHABET.chars().collect();
+        super::suggestions(word, lib, &alphabet, 2, k)
+    }
+
+    #[test]
+    fn test_ranked_by_distance_then_frequency() {
+        let lib = fixture();
+        assert_eq!(suggestions("ho", &lib, 3),
+                   vec![Suggestion::new("who", 1, 2),
+                        Suggestion::new("how", 1, 1),
+                        Suggestion::new("hey", 2, 1)]);
+    }
+
+    #[test]
+    fn test_known_word_comes_first() {
+        let lib = fixture();
+        assert_eq!(suggestions("how", &lib, 2),
+                   vec![Suggestion::new("how", 0, 1), Suggestion::new("who", 2, 2)]);
+    }
+
+    #[test]
+    fn test_third_level() {
+        let lib = fixture();
+        let alphabet: Vec<char> = ALPHABET.chars().collect();
+        assert_eq!(super::suggestions("hxxz", &lib, &alphabet, 3, 5),
+                   vec![Suggestion::new("hey", 3, 1),
+                        Suggestion::new("how", 3, 1)]);
+        assert!(super::suggestions("hxxz", &lib, &alphabet, 2, 5).is_empty());
+    }
+
+    #[test]
+    fn test_within_finds_every_level() {
+        let lib = fixture();
+        let alphabet: Vec<char> = ALPHABET.chars().collect();
+        let mut v = super::within("ho", &lib, &alphabet, 2);
+        v.sort_by(Suggestion::rank);
+        assert_eq!(v, vec![Suggestion::new("who", 1, 2),
+                           Suggestion::new("how", 1, 1),
+                           Suggestion::new("hey", 2, 1)]);
+    }
+
+    #[test]
+    fn test_stops_at_first_level_when_full() {
+        let lib = fixture();
+        assert_eq!(suggestions("ho", &lib, 1), vec![Suggestion::new("who", 1, 2)]);
+    }
+
+    fn fixture() -> input::CountTable {
+        let mut h = input::CountTable::new();
+        h.insert("how".to_string(), 1);
+        h.insert("who".to_string(), 2);
+        h.insert("hey".to_string(), 1);
+        h
+    }
+
+}
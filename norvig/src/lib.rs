@@ -0,0 +1,13 @@
+#![doc="
+Norvig spelling corrector.
+
+See the `edits` module for how candidate corrections are generated, the
+`channel` module for the optional noisy channel ranking, and the `model` module
+for the `Corrector` implementation.
+"]
+
+pub mod channel;
+pub mod edits;
+mod model;
+
+pub use crate::model::{Norvig, DEFAULT_MAX_DISTANCE};
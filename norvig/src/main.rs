@@ -1,4 +1,4 @@
-#[doc="
+#![doc="
 Input:
 * Inserts each word in the given training file into a hashmap, where the key is
   the word and the value is the number of times the word has been encountered.
@@ -9,7 +9,8 @@ Input:
   training file, the program attempts to find the word with the least edit
   distance between it and the input word. In the case of a tie, the word with
   the highest frequency in the training file is chosen. If no words are found
-  within an edit distance of 2, the program is unable to make a suggestion.
+  within an edit distance of 2 (see `--max-distance`), the program is unable
+  to make a suggestion.
 
 Output:
 * The program prints each word from stdin on a separate line.
@@ -19,6 +20,26 @@ Output:
 * If the word was not found in the training file but the program did not find
   a suggested correction, a dash is printed next to the input word.
 
+Edits replace and insert letters from every letter seen in the training file,
+so corpora in languages like Spanish or German get corrections involving 'ñ'
+or 'ß'. Pass `--alphabet LETTERS` to use a fixed set of letters instead, e.g.
+`--alphabet abcdefghijklmnopqrstuvwxyz`.
+
+`--error-model FILE` ranks corrections with a noisy channel model, by how
+frequent each word is times how likely the edits from it to the input word
+are, trained from a file of misspellings and their corrections (see the
+`channel` module). A known word is still printed alone. By default
+corrections are ranked by edit distance, then frequency. With `--context`
+only the closest corrections are ranked by the words around them, as
+without an error model, so the error model only orders the corrections
+further away and can no longer prefer a more distant word.
+
+`--max-distance N` looks for corrections up to N edits away instead of 2.
+Beyond 2 edits, known words are measured against the input word instead of
+generating every edit. `--max-distance adaptive` allows 1 edit for words of up
+to 4 letters, 2 for words of up to 12 letters, and 3 for longer words, and
+`--max-distance 25%` allows edits for a quarter of the word's letters.
+
 ex.
   bed
   qhair, chair
@@ -27,25 +48,36 @@ ex.
 
 use std::{env, fs, io};
 
-mod input;
-mod edits;
+use norvig::Norvig;
+use norvig::channel::{self, ErrorModel};
+use spellcore::cli::{self, Args};
+use spellcore::distance::MaxDistance;
+use spellcore::input::Document;
 
 fn main() {
-    let args: Vec<_> = env::args().collect();
-    if args.len() != 2 {
-        panic!("Pass in one argument--the name of the training file");
+    let value_flags = [cli::VALUE_FLAGS, &["--alphabet", "--error-model"]].concat();
+    let args = Args::parse(env::args().skip(1), &value_flags, cli::SWITCH_FLAGS)
+        .unwrap_or_else(|e| panic!("{}", e));
+    let alphabet: Option<String> = args.value("--alphabet").unwrap_or_else(|e| panic!("{}", e));
+    let max_distance: Option<MaxDistance> = args.value("--max-distance")
+        .unwrap_or_else(|e| panic!("{}", e));
+    let training = cli::Training::read(&args);
+    let word_library = training.counts();
+    let document = Document::from_reader(io::stdin())
+        .unwrap_or_else(|e| panic!("Error reading stdin: {}", e));
+
+    let mut norvig = match alphabet {
+        Some(letters) => Norvig::with_alphabet(word_library, letters.chars().collect()),
+        None => Norvig::new(word_library),
+    };
+    if let Some(max_distance) = max_distance {
+        norvig.set_max_distance(max_distance);
     }
-    let mut word_library = input::CountTable::new();
-    let f = fs::File::open(&args[1]).expect("Error opening the training file");
-    
-    input::read_and_count(&mut word_library, f);
-    let v: Vec<String> = input::read_input(io::stdin());
-    
-    for word in &v {
-        if word_library.contains_key(word) {
-            println!("{}", word);
-        } else {
-            println!("{}, {}", word, edits::correct(word, &word_library));
-        }
+    if let Some(path) = args.value::<String>("--error-model").unwrap_or_else(|e| panic!("{}", e)) {
+        let f = fs::File::open(path).expect("Error opening the error model file");
+        let pairs = channel::read_pairs(f).unwrap_or_else(|e| panic!("{}", e));
+        norvig.set_error_model(ErrorModel::from_pairs(&pairs));
     }
+    cli::print_document(&norvig, &training, &document, &args);
 }
+
@@ -0,0 +1,203 @@
+#![doc="
+Norvig
+Wraps the word frequencies read from the training file so that the Norvig
+corrector can be used through the `Corrector` trait.
+
+word_library: maps each word in the training file to its frequency
+alphabet: the letters used to generate edits, by default every letter seen in
+  the training file
+max_distance: how many edits away from a word to look for corrections, by
+  default 2
+error_model: when set, corrections are ranked by the noisy channel model (see
+  the `channel` module) instead of by distance then frequency
+"]
+
+use spellcore::{Correction, CountTable, Corrector, Suggestion};
+use spellcore::distance::MaxDistance;
+
+use crate::channel::ErrorModel;
+use crate::edits;
+
+pub const DEFAULT_MAX_DISTANCE: MaxDistance = MaxDistance::Fixed(2);
+
+pub struct Norvig {
+    word_library: CountTable,
+    alphabet: Vec<char>,
+    max_distance: MaxDistance,
+    error_model: Option<ErrorModel>,
+}
+
+impl Norvig {
+
+    pub fn new(word_library: CountTable) -> Norvig {
+        let alphabet = edits::alphabet_from(&word_library);
+        Norvig::with_alphabet(word_library, alphabet)
+    }
+
+    pub fn with_alphabet(word_library: CountTable, alphabet: Vec<char>) -> Norvig {
+        Norvig {
+            word_library,
+            alphabet,
+            max_distance: DEFAULT_MAX_DISTANCE,
+            error_model: None,
+        }
+    }
+
+    pub fn set_max_distance(&mut self, max_distance: MaxDistance) {
+        self.max_distance = max_distance;
+    }
+
+    pub fn set_error_model(&mut self, error_model: ErrorModel) {
+        self.error_model = Some(error_model);
+    }
+
+    pub fn word_library(&self) -> &CountTable {
+        &self.word_library
+    }
+
+}
+
+impl Corrector for Norvig {
+
+    fn check(&self, word: &str) -> bool {
+        self.word_library.contains_key(word)
+    }
+
+    fn suggest(&self, word: &str) -> Correction {
+        match self.error_model {
+            Some(_) if self.check(word) => Correction::Known,
+            Some(_) => match self.suggest_top_k(word, 1).into_iter().next() {
+                Some(best) => best.into_correction(),
+                None => Correction::NoSuggestion,
+            },
+            None => edits::correct(word, &self.word_library, &self.alphabet,
+                                   self.max_distance.for_word(word)),
+        }
+    }
+
+    fn suggest_top_k(&self, word: &str, k: usize) -> Vec<Suggestion> {
+        self.suggest_within(word, self.max_distance.for_word(word), k)
+    }
+
+    fn suggest_within(&self, word: &str, max_distance: usize, k: usize) -> Vec<Suggestion> {
+        let max_distance = max_distance.min(self.max_distance.for_word(word));
+        match self.error_model {
+            // Every level of edits is scored, since a likely edit to a common
+            // word can beat a closer but unlikely one, but a known word is
+            // still its own best candidate
+            Some(ref model) => {
+                let mut v = edits::within(word, &self.word_library, &self.alphabet, max_distance);
+                let known = v.iter().filter(|s| s.distance == 0).count();
+                v.sort_by_key(|s| s.distance != 0);
+                model.rank(word, &mut v[known..]);
+                v.truncate(k);
+                v
+            }
+            None => edits::suggestions(word, &self.word_library, &self.alphabet, max_distance, k),
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod norvig_tests {
+
+    use spellcore::{Correction, CountTable, Corrector, Suggestion};
+    use spellcore::distance::MaxDistance;
+    use crate::channel::ErrorModel;
+    use super::Norvig;
+
+    #[test]
+    fn test_check() {
+        let n = fixture();
+        assert!(n.check("who"));
+        assert!(!n.check("ho"));
+    }
+
+    #[test]
+    fn test_suggest() {
+        let n = fixture();
+        assert_eq!(n.suggest("who"), Correction::Known);
+        assert_eq!(n.suggest("wherf"),
+                   Correction::Suggested { word: "where".to_string(), distance: 1, frequency: 2 });
+        assert_eq!(n.suggest("zzzzzz"), Correction::NoSuggestion);
+    }
+
+    #[test]
+    fn test_suggest_top_k() {
+        let n = fixture();
+        assert_eq!(n.suggest_top_k("ho", 2),
+                   vec![Suggestion::new("who", 1, 2), Suggestion::new("how", 1, 1)]);
+        assert_eq!(n.suggest_top_k("ho", 1), vec![Suggestion::new("who", 1, 2)]);
+        assert!(n.suggest_top_k("zzzzzz", 3).is_empty());
+    }
+
+    #[test]
+    fn test_suggest_within() {
+        let n = fixture();
+        assert_eq!(n.suggest_within("who", 1, 10),
+                   vec![Suggestion::new("who", 0, 2), Suggestion::new("why", 1, 1)]);
+        assert_eq!(n.suggest_within("wxo", 0, 10), vec![]);
+    }
+
+    #[test]
+    fn test_configured_alphabet() {
+        let mut h = CountTable::new();
+        h.insert("über".to_string(), 1);
+        let n = Norvig::with_alphabet(h.clone(), vec!['b', 'e', 'r']);
+        assert_eq!(n.suggest("uber"), Correction::NoSuggestion);
+        let n = Norvig::new(h);
+        assert_eq!(n.suggest("uber"),
+                   Correction::Suggested { word: "über".to_string(), distance: 1, frequency: 1 });
+    }
+
+    #[test]
+    fn test_adaptive_max_distance() {
+        let mut h = CountTable::new();
+        h.insert("how".to_string(), 1);
+        h.insert("electroencephalogram".to_string(), 1);
+        let mut n = Norvig::new(h);
+        n.set_max_distance(MaxDistance::Adaptive);
+        assert_eq!(n.suggest("hxx"), Correction::NoSuggestion);
+        assert_eq!(n.suggest("electroensefalogram"),
+                   Correction::Suggested {
+                       word: "electroencephalogram".to_string(),
+                       distance: 3,
+                       frequency: 1,
+                   });
+    }
+
+    #[test]
+    fn test_error_model_ranking() {
+        let mut h = CountTable::new();
+        h.insert("bat".to_string(), 1);
+        h.insert("boot".to_string(), 10);
+        let mut n = Norvig::new(h);
+        assert_eq!(n.suggest("bxt"),
+                   Correction::Suggested { word: "bat".to_string(), distance: 1, frequency: 1 });
+
+        n.set_error_model(ErrorModel::new());
+        assert_eq!(n.suggest("bat"), Correction::Known);
+        assert_eq!(n.suggest("bxt"),
+                   Correction::Suggested { word: "boot".to_string(), distance: 2, frequency: 10 });
+        assert_eq!(n.suggest_top_k("bxt", 2),
+                   vec![Suggestion::new("boot", 2, 10), Suggestion::new("bat", 1, 1)]);
+        assert_eq!(n.suggest("zzzzzz"), Correction::NoSuggestion);
+
+        // A frequent word two edits away doesn't outrank a known word
+        assert_eq!(n.suggest_top_k("bat", 2),
+                   vec![Suggestion::new("bat", 0, 1), Suggestion::new("boot", 2, 10)]);
+        assert_eq!(n.suggest_within("bat", 2, 1), vec![Suggestion::new("bat", 0, 1)]);
+    }
+
+    fn fixture() -> Norvig {
+        let mut h = CountTable::new();
+        h.insert("why".to_string(), 1);
+        h.insert("where".to_string(), 2);
+        h.insert("when".to_string(), 1);
+        h.insert("how".to_string(), 1);
+        h.insert("who".to_string(), 2);
+        Norvig::new(h)
+    }
+
+}
@@ -0,0 +1,194 @@
+#![doc="
+Words are looked up in lowercase by default, so corrections are re-cased to
+match how the misspelled word was written before they are printed.
+
+* A word written in capitals, like `TEH`, gets its correction in capitals.
+* A word starting with a capital, like `Teh` at the start of a sentence, gets
+  its correction with a capital first letter.
+* Any other word gets its correction as it is in the dictionary: in lowercase,
+  or with `--case-sensitive`, as it was written in the training file, so
+  `london` gets `London`.
+
+Words that are usually capitalized in the training file, like `London` or
+`NASA`, keep the casing they were learned with unless the misspelled word was
+written in capitals. Words starting a sentence aren't counted when learning
+casings, so `The` or `Something` there doesn't make them capitalized. A split
+word is re-cased word by word, with only its first word taking a capital first
+letter from the misspelled word.
+"]
+
+use std::collections::HashMap;
+
+use crate::input::Document;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Case {
+    Lower,
+    Title,
+    Upper,
+}
+
+impl Case {
+
+    // How word was written; words of mixed case, like `iPhone`, count as
+    // lowercase
+    pub fn of(word: &str) -> Case {
+        let letters: Vec<char> = word.chars().filter(|c| c.is_alphabetic()).collect();
+        if letters.len() > 1 && letters.iter().all(|c| c.is_uppercase()) {
+            Case::Upper
+        } else if letters.first().is_some_and(|c| c.is_uppercase()) {
+            Case::Title
+        } else {
+            Case::Lower
+        }
+    }
+
+    pub fn apply(&self, word: &str) -> String {
+        match *self {
+            Case::Lower => word.to_lowercase(),
+            Case::Upper => word.to_uppercase(),
+            Case::Title => {
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars).collect(),
+                    None => String::new(),
+                }
+            }
+        }
+    }
+
+}
+
+#[derive(Default)]
+pub struct Casing {
+    // Maps each lowercase word to its usual casing in the training file, if
+    // that isn't lowercase
+    learned: HashMap<String, String>,
+}
+
+impl Casing {
+
+    pub fn from_document(document: &Document) -> Casing {
+        let mut forms: HashMap<&str, HashMap<&str, usize>> = HashMap::new();
+        let tokens = &document.tokens;
+        for (i, t) in tokens.iter().enumerate().skip(1) {
+            if document.gap(i - 1).contains(['.', '!', '?']) {
+                continue;
+            }
+            *forms.entry(&t.key).or_default().entry(&t.text).or_insert(0) += 1;
+        }
+
+        let mut learned = HashMap::new();
+        for (key, counts) in forms {
+            // Ties go to lowercase, then to the first form alphabetically
+            let lower = counts.get(key).cloned().unwrap_or(0);
+            let best = counts.iter()
+                .filter(|&(_, &c)| c > lower)
+                .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)));
+            if let Some((form, _)) = best {
+                learned.insert(key.to_string(), form.to_string());
+            }
+        }
+        Casing { learned }
+    }
+
+    // The learned casing of the lowercase word, if it has one
+    pub fn learned(&self, word: &str) -> Option<&str> {
+        self.learned.get(word).map(|w| &w[..])
+    }
+
+    // The suggestion for a word written as written, re-cased to match it
+    pub fn recase(&self, written: &str, suggestion: &str) -> String {
+        let case = Case::of(written);
+        let words: Vec<String> = suggestion.split(' ').enumerate()
+            .map(|(i, w)| {
+                let case = if i == 0 || case == Case::Upper { case } else { Case::Lower };
+                match self.learned(w) {
+                    Some(learned) if case != Case::Upper => learned.to_string(),
+                    _ if case == Case::Lower => w.to_string(),
+                    _ => case.apply(w),
+                }
+            })
+            .collect();
+        words.join(" ")
+    }
+
+}
+
+#[cfg(test)]
+mod case_tests {
+
+    use crate::input::Document;
+    use super::{Case, Casing};
+
+    fn learn(s: &str) -> Casing {
+        Casing::from_document(&Document::from_reader(s.as_bytes()).unwrap())
+    }
+
+    #[test]
+    fn recognizes_cases() {
+        assert_eq!(Case::of("teh"), Case::Lower);
+        assert_eq!(Case::of("Teh"), Case::Title);
+        assert_eq!(Case::of("TEH"), Case::Upper);
+        assert_eq!(Case::of("I"), Case::Title);
+        assert_eq!(Case::of("DON'T"), Case::Upper);
+        assert_eq!(Case::of("iPhone"), Case::Lower);
+        assert_eq!(Case::of("Éclair"), Case::Title);
+    }
+
+    #[test]
+    fn applies_cases() {
+        assert_eq!(Case::Title.apply("the"), "The");
+        assert_eq!(Case::Upper.apply("don't"), "DON'T");
+        assert_eq!(Case::Lower.apply("The"), "the");
+        assert_eq!(Case::Title.apply("éclair"), "Éclair");
+        assert_eq!(Case::Title.apply(""), "");
+        assert_eq!(Case::Title.apply("mcDonald"), "McDonald");
+    }
+
+    #[test]
+    fn matches_the_written_word() {
+        let casing = Casing::default();
+        assert_eq!(casing.recase("Teh", "the"), "The");
+        assert_eq!(casing.recase("TEH", "the"), "THE");
+        assert_eq!(casing.recase("teh", "the"), "the");
+        assert_eq!(casing.recase("Thequickbrown", "the quick brown"), "The quick brown");
+        assert_eq!(casing.recase("THEQUICK", "the quick"), "THE QUICK");
+        assert_eq!(casing.recase("london", "London"), "London");
+        assert_eq!(casing.recase("Nsaa", "NASA"), "NASA");
+    }
+
+    #[test]
+    fn keeps_learned_casing() {
+        let casing = learn("London is big . The city of London . NASA and Nasa and NASA . \
+                            the The the");
+        assert_eq!(casing.learned("london"), Some("London"));
+        assert_eq!(casing.learned("nasa"), Some("NASA"));
+        assert_eq!(casing.learned("the"), None);
+        assert_eq!(casing.learned("is"), None);
+        assert_eq!(casing.recase("lodnon", "london"), "London");
+        assert_eq!(casing.recase("LODNON", "london"), "LONDON");
+        assert_eq!(casing.recase("nsaa", "nasa"), "NASA");
+        assert_eq!(casing.recase("Inlondon", "in london"), "In London");
+    }
+
+    #[test]
+    fn ties_go_to_lowercase() {
+        let casing = learn("The the Apple apple");
+        assert_eq!(casing.learned("the"), None);
+        assert_eq!(casing.learned("apple"), None);
+    }
+
+    #[test]
+    fn skips_words_starting_sentences() {
+        let casing = learn("Something odd. Something else! Phonetic? We said so.\n\n\
+                            He did. something");
+        assert_eq!(casing.learned("something"), None);
+        assert_eq!(casing.learned("phonetic"), None);
+        assert_eq!(casing.learned("we"), None);
+        assert_eq!(casing.learned("he"), None);
+        assert_eq!(casing.recase("sometihng", "something"), "something");
+        assert_eq!(casing.learned("odd"), None);
+    }
+
+}
@@ -0,0 +1,458 @@
+#![doc="
+Checks every word of a text with a `Corrector`, using the words around each
+one when the options that need them are turned on.
+
+* With a `LanguageModel`, the closest candidates for a misspelled word are
+  ranked by how well they fit between its neighbours instead of by frequency
+  alone. Candidates further away still come after them, in the corrector's
+  order, so a corrector that ranks a more distant word first, like the Norvig
+  corrector with an error model, has that choice overridden.
+* With `ConfusionSets` as well, known words are checked for real-word errors:
+  a known word is flagged if a member of its confusion set, or a known word one
+  edit away, was seen next to one of its neighbours in training and fits its
+  context much better than it does. Edit neighbours have to fit far better,
+  since most of them are not mistakes.
+* With a `Segmenter`, a misspelled word with no correction one edit away is
+  split into known words if it can be, like `thequickbrown` into
+  `the quick brown`. The split is suggested as a single suggestion, one edit
+  per space inserted, with the frequency of its rarest word.
+* With merging turned on as well as a language model, two adjacent words are
+  joined when together they make a known word, like `some thing`, and either
+  one isn't a known word or the joined word was seen in training more often
+  than the two words one after the other.
+* With a `PhoneticIndex`, a misspelled word the corrector has no candidates
+  for gets the known words that sound like it instead, like `phonetic` for
+  `fonetik`, closest and most frequent first.
+"]
+
+use crate::confusion::ConfusionSets;
+use crate::corrector::{Correction, Corrector, Suggestion};
+use crate::distance::damerau_levenshtein_distance;
+use crate::ngram::LanguageModel;
+use crate::phonetic::PhoneticIndex;
+use crate::segment::Segmenter;
+
+// How many of the corrector's suggestions are reranked by context
+pub const CONTEXT_CANDIDATES: usize = 10;
+
+// How many times better than a known word a member of its confusion set, or
+// one of its edit neighbours, has to fit the context to flag it
+pub const CONFUSION_RATIO: f64 = 10.0;
+pub const NEIGHBOUR_RATIO: f64 = 1000.0;
+
+pub struct Checker<'a, C: Corrector + ?Sized> {
+    corrector: &'a C,
+    language_model: Option<LanguageModel>,
+    confusion_sets: Option<ConfusionSets>,
+    segmenter: Option<Segmenter>,
+    phonetic_index: Option<PhoneticIndex>,
+    merge: bool,
+}
+
+impl<'a, C: Corrector + ?Sized> Checker<'a, C> {
+
+    pub fn new(corrector: &'a C) -> Checker<'a, C> {
+        Checker {
+            corrector,
+            language_model: None,
+            confusion_sets: None,
+            segmenter: None,
+            phonetic_index: None,
+            merge: false,
+        }
+    }
+
+    pub fn set_language_model(&mut self, language_model: LanguageModel) {
+        self.language_model = Some(language_model);
+    }
+
+    // Real-word errors are only detected with a language model too
+    pub fn set_confusion_sets(&mut self, confusion_sets: ConfusionSets) {
+        self.confusion_sets = Some(confusion_sets);
+    }
+
+    pub fn set_segmenter(&mut self, segmenter: Segmenter) {
+        self.segmenter = Some(segmenter);
+    }
+
+    // Only used for words the corrector has no candidates for
+    pub fn set_phonetic_index(&mut self, phonetic_index: PhoneticIndex) {
+        self.phonetic_index = Some(phonetic_index);
+    }
+
+    // Merging is only done with a language model too
+    pub fn set_merge(&mut self, merge: bool) {
+        self.merge = merge;
+    }
+
+    // The known word words[i] and words[i+1] should be joined into, if any.
+    // Joining them is one edit. gap is the text between them, and they are
+    // only joined if it is nothing but spaces, so `some. Thing` stays apart.
+    pub fn merged(&self, words: &[String], i: usize, gap: &str) -> Option<Suggestion> {
+        let lm = self.language_model.as_ref().filter(|_| self.merge)?;
+        if !gap.chars().all(|c| c == ' ') {
+            return None;
+        }
+        let (first, second) = (words.get(i)?, words.get(i + 1)?);
+        let joined = format!("{}{}", first, second);
+        if !self.corrector.check(&joined) {
+            return None;
+        }
+        let count = lm.count(&joined);
+        if self.corrector.check(first) && self.corrector.check(second)
+            && count <= lm.bigram_count(first, second) {
+            return None;
+        }
+        Some(Suggestion::new(&joined, 1, count))
+    }
+
+    pub fn check(&self, words: &[String], i: usize) -> bool {
+        self.corrector.check(&words[i]) && self.real_word_alternatives(words, i).is_empty()
+    }
+
+    // None if words[i] passes check, or else up to k candidates for it, best
+    // first. Cheaper than calling both, since a known word's context is only
+    // checked once.
+    pub fn review(&self, words: &[String], i: usize, k: usize) -> Option<Vec<Suggestion>> {
+        if !self.corrector.check(&words[i]) {
+            return Some(self.suggest_top_k(words, i, k));
+        }
+        let alternatives = self.real_word_alternatives(words, i);
+        match self.language_model {
+            Some(ref lm) if !alternatives.is_empty() => {
+                Some(with_flagged_word(alternatives, &words[i], lm, k))
+            }
+            _ => None,
+        }
+    }
+
+    // The correction for words[i]
+    pub fn suggest(&self, words: &[String], i: usize) -> Correction {
+        if self.language_model.is_none() && self.segmenter.is_none()
+            && self.phonetic_index.is_none() {
+            return self.corrector.suggest(&words[i]);
+        }
+        if self.corrector.check(&words[i]) {
+            return match self.real_word_alternatives(words, i).into_iter().next() {
+                Some(best) => best.into_correction(),
+                None => Correction::Known,
+            };
+        }
+        match self.suggest_top_k(words, i, 1).into_iter().next() {
+            Some(best) => best.into_correction(),
+            None => Correction::NoSuggestion,
+        }
+    }
+
+    // Up to k candidates for words[i], best first
+    pub fn suggest_top_k(&self, words: &[String], i: usize, k: usize) -> Vec<Suggestion> {
+        let mut v = self.ranked(words, i, k);
+        if let Some(ref index) = self.phonetic_index {
+            if v.is_empty() && !self.corrector.check(&words[i]) {
+                v = index.lookup(&words[i], k);
+            }
+        }
+        if let Some(ref segmenter) = self.segmenter {
+            if !v.iter().any(|s| s.distance <= 1) {
+                if let Some(pieces) = segmenter.segment(&words[i]) {
+                    let frequency = pieces.iter().map(|w| segmenter.frequency(w)).min();
+                    let split = Suggestion::new(&pieces.join(" "), pieces.len() - 1,
+                                                frequency.unwrap_or(0));
+                    v.insert(0, split);
+                    v.truncate(k);
+                }
+            }
+        }
+        v
+    }
+
+    // Up to k of the corrector's candidates for words[i], ranked by context
+    // when there is a language model
+    fn ranked(&self, words: &[String], i: usize, k: usize) -> Vec<Suggestion> {
+        let lm = match self.language_model {
+            Some(ref lm) => lm,
+            None => return self.corrector.suggest_top_k(&words[i], k),
+        };
+
+        let alternatives = self.real_word_alternatives(words, i);
+        if !alternatives.is_empty() {
+            return with_flagged_word(alternatives, &words[i], lm, k);
+        }
+
+        let v = self.corrector.suggest_top_k(&words[i], k.max(CONTEXT_CANDIDATES));
+
+        // Only the closest candidates compete on context, so a word that fits
+        // well is never preferred over a closer one
+        let closest = v.iter().map(|s| s.distance).min();
+        let (mut ranked, rest): (Vec<Suggestion>, Vec<Suggestion>) = v.into_iter()
+            .partition(|s| Some(s.distance) == closest);
+        let mut scored: Vec<(f64, Suggestion)> = ranked.drain(..)
+            .map(|s| (lm.context_score(words, i, &s.word), s))
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        ranked.extend(scored.into_iter().map(|(_, s)| s));
+        ranked.extend(rest);
+        ranked.truncate(k);
+        ranked
+    }
+
+    // Known words that fit the context of the known word words[i] much better
+    // than it does, best first
+    fn real_word_alternatives(&self, words: &[String], i: usize) -> Vec<Suggestion> {
+        let (lm, sets) = match (&self.language_model, &self.confusion_sets) {
+            (Some(lm), Some(sets)) => (lm, sets),
+            _ => return Vec::new(),
+        };
+        let word = &words[i];
+        if !self.corrector.check(word) {
+            return Vec::new();
+        }
+
+        let mut candidates: Vec<(f64, Suggestion)> = Vec::new();
+        for other in sets.confusions(word) {
+            if self.corrector.check(other) {
+                let distance = damerau_levenshtein_distance(word, other);
+                candidates.push((CONFUSION_RATIO, Suggestion::new(other, distance, lm.count(other))));
+            }
+        }
+        for s in self.corrector.suggest_within(word, 1, CONTEXT_CANDIDATES) {
+            if s.distance == 1 && candidates.iter().all(|c| c.1.word != s.word) {
+                candidates.push((NEIGHBOUR_RATIO, s));
+            }
+        }
+
+        let score = lm.context_score(words, i, word);
+        let mut flagged: Vec<(f64, Suggestion)> = candidates.into_iter()
+            .filter(|c| lm.seen_in_context(words, i, &c.1.word))
+            .map(|(ratio, s)| (ratio, lm.context_score(words, i, &s.word), s))
+            .filter(|&(ratio, alternative, _)| alternative >= ratio * score)
+            .map(|(_, alternative, s)| (alternative, s))
+            .collect();
+        flagged.sort_by(|a, b| b.0.total_cmp(&a.0));
+        flagged.into_iter().map(|(_, s)| s).collect()
+    }
+
+}
+
+// A flagged real word comes after the words that fit better
+fn with_flagged_word(mut alternatives: Vec<Suggestion>, word: &str, lm: &LanguageModel,
+                     k: usize) -> Vec<Suggestion> {
+    alternatives.push(Suggestion::new(word, 0, lm.count(word)));
+    alternatives.truncate(k);
+    alternatives
+}
+
+#[cfg(test)]
+mod checker_tests {
+
+    use crate::confusion::ConfusionSets;
+    use crate::corrector::{Correction, Corrector, Suggestion};
+    use crate::input::CountTable;
+    use crate::ngram::LanguageModel;
+    use crate::phonetic::{Phonetic, PhoneticIndex};
+    use crate::segment::Segmenter;
+    use super::Checker;
+
+    // Knows "from", "form" and "farm", and suggests them in that order for
+    // anything else, "farm" being further away
+    struct Fixed;
+
+    impl Corrector for Fixed {
+        fn check(&self, word: &str) -> bool {
+            ["from", "form", "farm"].contains(&word)
+        }
+
+        fn suggest(&self, _: &str) -> Correction {
+            Correction::Suggested { word: "from".to_string(), distance: 1, frequency: 3 }
+        }
+
+        fn suggest_top_k(&self, _: &str, k: usize) -> Vec<Suggestion> {
+            let mut v = vec![Suggestion::new("from", 1, 3),
+                             Suggestion::new("form", 1, 2),
+                             Suggestion::new("farm", 2, 9)];
+            v.truncate(k);
+            v
+        }
+    }
+
+    fn words(s: &str) -> Vec<String> {
+        s.split_whitespace().map(|w| w.to_string()).collect()
+    }
+
+    fn checker() -> Checker<'static, Fixed> {
+        let mut checker = Checker::new(&Fixed);
+        let training = words("fill in the form and the form came from home to the farm");
+        checker.set_language_model(LanguageModel::from_words(&training));
+        checker
+    }
+
+    #[test]
+    fn without_context_uses_the_corrector() {
+        let checker = Checker::new(&Fixed);
+        let text = words("fill in the fomr");
+        assert_eq!(checker.suggest(&text, 3),
+                   Correction::Suggested { word: "from".to_string(), distance: 1, frequency: 3 });
+        assert!(!checker.check(&text, 3));
+    }
+
+    #[test]
+    fn ranks_closest_candidates_by_context() {
+        let checker = checker();
+        let text = words("fill in the fomr");
+        assert_eq!(checker.suggest(&text, 3),
+                   Correction::Suggested { word: "form".to_string(), distance: 1, frequency: 2 });
+        let text = words("came fomr home");
+        assert_eq!(checker.suggest(&text, 1),
+                   Correction::Suggested { word: "from".to_string(), distance: 1, frequency: 3 });
+    }
+
+    #[test]
+    fn keeps_further_candidates_last() {
+        let checker = checker();
+        let text = words("to the fomr");
+        assert_eq!(checker.suggest_top_k(&text, 2, 3)[2], Suggestion::new("farm", 2, 9));
+    }
+
+    #[test]
+    fn known_words_are_not_changed() {
+        let checker = checker();
+        assert_eq!(checker.suggest(&words("came form home"), 1), Correction::Known);
+    }
+
+    #[test]
+    fn splits_run_together_words() {
+        let corrector = Words(words("the quick brown fox then"));
+        let mut counts = CountTable::new();
+        for w in &corrector.0 {
+            counts.insert(w.to_string(), 2);
+        }
+        counts.insert("the".to_string(), 5);
+        let mut checker = Checker::new(&corrector);
+        checker.set_segmenter(Segmenter::new(counts));
+
+        let text = words("thequickbrown then thenn");
+        assert_eq!(checker.suggest(&text, 0),
+                   Correction::Suggested { word: "the quick brown".to_string(), distance: 2,
+                                           frequency: 2 });
+        assert_eq!(checker.suggest(&text, 1), Correction::Known);
+        assert_eq!(checker.suggest_top_k(&text, 0, 3),
+                   vec![Suggestion::new("the quick brown", 2, 2)]);
+    }
+
+    #[test]
+    fn falls_back_to_words_that_sound_alike() {
+        let corrector = Words(words("phonetic fanatic knowledge then than"));
+        let mut counts = CountTable::new();
+        for (w, c) in &[("phonetic", 2), ("fanatic", 1), ("knowledge", 5), ("then", 3)] {
+            counts.insert(w.to_string(), *c);
+        }
+        let mut checker = Checker::new(&corrector);
+        checker.set_phonetic_index(PhoneticIndex::new(Phonetic::Metaphone, &counts));
+
+        let text = words("fonetik nollij then thenn");
+        assert_eq!(checker.suggest(&text, 0),
+                   Correction::Suggested { word: "phonetic".to_string(), distance: 3,
+                                           frequency: 2 });
+        assert_eq!(checker.suggest_top_k(&text, 0, 3),
+                   vec![Suggestion::new("phonetic", 3, 2), Suggestion::new("fanatic", 3, 1)]);
+        assert_eq!(checker.suggest(&text, 1),
+                   Correction::Suggested { word: "knowledge".to_string(), distance: 6,
+                                           frequency: 5 });
+        assert_eq!(checker.suggest(&text, 2), Correction::Known);
+        assert_eq!(checker.suggest_top_k(&text, 3, 1), vec![Suggestion::new("then", 1, 3)]);
+    }
+
+    #[test]
+    fn merges_split_words() {
+        let corrector = Words(words("i saw something and every one saw it . something \
+                                     else . spelling is hard . every one of us . everyone"));
+        let mut checker = Checker::new(&corrector);
+        checker.set_language_model(LanguageModel::from_words(&corrector.0));
+        assert_eq!(checker.merged(&words("some thing"), 0, " "), None);
+
+        checker.set_merge(true);
+        let text = words("some thing every one spel ling everyone");
+        assert_eq!(checker.merged(&text, 0, " "), Some(Suggestion::new("something", 1, 2)));
+        assert_eq!(checker.merged(&text, 1, " "), None);
+        assert_eq!(checker.merged(&text, 2, " "), None);
+        assert_eq!(checker.merged(&text, 4, " "), Some(Suggestion::new("spelling", 1, 1)));
+        assert_eq!(checker.merged(&text, 6, " "), None);
+        assert_eq!(checker.merged(&text, 0, ". "), None);
+        assert_eq!(checker.merged(&text, 0, "\n"), None);
+        assert_eq!(checker.merged(&text, 0, "  "), Some(Suggestion::new("something", 1, 2)));
+    }
+
+    // Knows every word in its training text, and suggests "then" and "than"
+    // for each other
+    struct Words(Vec<String>);
+
+    impl Corrector for Words {
+        fn check(&self, word: &str) -> bool {
+            self.0.iter().any(|w| w == word)
+        }
+
+        fn suggest(&self, _: &str) -> Correction {
+            Correction::NoSuggestion
+        }
+
+        fn suggest_top_k(&self, word: &str, _: usize) -> Vec<Suggestion> {
+            match word {
+                "then" => vec![Suggestion::new("then", 0, 2), Suggestion::new("than", 1, 2)],
+                "than" => vec![Suggestion::new("than", 0, 2), Suggestion::new("then", 1, 2)],
+                _ => Vec::new(),
+            }
+        }
+    }
+
+    const TRAINING: &str = "she is taller than him and then she left . \
+                            it is better than that and then we ate . their house is there";
+
+    fn real_word_checker<'a>(corrector: &'a Words, sets: &str) -> Checker<'a, Words> {
+        let mut checker = Checker::new(corrector);
+        checker.set_language_model(LanguageModel::from_words(&corrector.0));
+        checker.set_confusion_sets(ConfusionSets::parse(sets));
+        checker
+    }
+
+    #[test]
+    fn flags_confused_real_words() {
+        let corrector = Words(words(TRAINING));
+        let checker = real_word_checker(&corrector, "their there");
+        let text = words("he is taller then him");
+        assert!(!checker.check(&text, 3));
+        assert_eq!(checker.suggest(&text, 3),
+                   Correction::Suggested { word: "than".to_string(), distance: 1, frequency: 2 });
+        assert_eq!(checker.suggest_top_k(&text, 3, 2),
+                   vec![Suggestion::new("than", 1, 2), Suggestion::new("then", 0, 2)]);
+        assert_eq!(checker.review(&text, 3, 2),
+                   Some(vec![Suggestion::new("than", 1, 2), Suggestion::new("then", 0, 2)]));
+
+        let text = words("and then she left");
+        assert!(checker.check(&text, 1));
+        assert_eq!(checker.suggest(&text, 1), Correction::Known);
+        assert_eq!(checker.review(&text, 1, 2), None);
+        assert_eq!(checker.review(&words("and thenn"), 1, 2), Some(Vec::new()));
+    }
+
+    #[test]
+    fn flags_confusion_set_members() {
+        let corrector = Words(words(TRAINING));
+        let checker = real_word_checker(&corrector, "their there");
+        let text = words("it is over their");
+        assert_eq!(checker.suggest(&text, 3), Correction::Known);
+        let text = words("their house is big");
+        assert_eq!(checker.suggest(&text, 0), Correction::Known);
+        let text = words("there house is big");
+        assert_eq!(checker.suggest(&text, 0),
+                   Correction::Suggested { word: "their".to_string(), distance: 2, frequency: 1 });
+    }
+
+    #[test]
+    fn needs_confusion_sets_to_flag_real_words() {
+        let corrector = Words(words(TRAINING));
+        let mut checker = Checker::new(&corrector);
+        checker.set_language_model(LanguageModel::from_words(&corrector.0));
+        assert_eq!(checker.suggest(&words("he is taller then him"), 3), Correction::Known);
+    }
+
+}
@@ -0,0 +1,664 @@
+#![doc="
+Command line handling shared by the corrector binaries.
+
+Usage: <corrector> [options] <training file>
+
+Options:
+* `--suggestions N`: print up to N ranked suggestions for each misspelled word
+  instead of only the best one.
+* `--max-distance D`: how far from a misspelled word to look for corrections.
+  D is a number of edits, `adaptive` to allow more edits for longer words, or
+  a percentage of the word's length such as `25%` (see
+  `spellcore::distance::MaxDistance`). The default is 2.
+* `--context`: also count pairs and triples of consecutive words in the
+  training file, and rank the closest corrections for each misspelled word by
+  how well they fit between the words around it (see `spellcore::ngram`).
+* `--real-word`: like `--context`, but also flag known words that are likely
+  mistakes for a word in the same confusion set, like 'than' for 'then', or
+  for a word one edit away, given the words around them. The flagged word is
+  printed with its replacement like a misspelled word.
+* `--confusion FILE`: like `--real-word`, with the confusion sets read from
+  FILE instead of the built-in ones (see `spellcore::confusion`).
+* `--segment`: split misspelled words that have no correction one edit away
+  into known words when possible (see `spellcore::segment`).
+* `--phonetic ALG`: when a misspelled word has no correction within the
+  maximum distance, suggest known words that sound like it, like `phonetic`
+  for `fonetik`. ALG is `soundex` or `metaphone` (see `spellcore::phonetic`).
+* `--merge`: join two adjacent words when together they make a known word
+  that is more common than the two apart, like `some thing`. Both words are
+  printed on one line, followed by the joined word.
+* `--case-sensitive`: count case variants of a word in the training file
+  separately, so `US` and `us` are different words and `london` is corrected
+  to `London`, or `nasa` and `Nasa` to `NASA`, at distance 0. A capitalized
+  word is still known if its lowercase form is, and a word in capitals if its
+  lowercase or capitalized form is.
+* `--positions`: start each line with where the word was found in the input:
+  its line and column, counted in chars from 1, and its byte range, as
+  `line:column:start-end: `.
+* `--fix`: print the input back out with each misspelled word replaced by its
+  best correction, keeping everything else, like whitespace, punctuation,
+  numbers and the casing of each word, as it was.
+
+ex. with `--segment` and `--merge`
+  thequickbrown, the quick brown
+  some thing, something
+
+ex. with `--positions`, for the input `the qhair\n  sflj`
+  1:1:0-3: the
+  1:5:4-9: qhair, chair
+  2:3:12-16: sflj, -
+
+ex. with `--fix`, for the input `Teh qhair, 2 sflj!`
+  The chair, 2 sflj!
+
+Misspelled words are printed as written, and their corrections are re-cased
+to match them (see `spellcore::case`).
+
+ex.
+  Teh, The
+  LODNON, LONDON
+
+With `--suggestions N`, each misspelled word is followed by up to N
+suggestions, best first. Each suggestion is followed by its distance from the
+input word and its frequency in the training file.
+
+ex.
+  bed
+  qhair, chair (1, 40), hair (1, 12)
+  sflj, -
+"]
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Read;
+use std::str::FromStr;
+
+use crate::case::{Case, Casing};
+use crate::checker::Checker;
+use crate::confusion::ConfusionSets;
+use crate::corrector::{Correction, Corrector, Suggestion};
+use crate::input::{CountTable, Document, Token};
+use crate::ngram::LanguageModel;
+use crate::phonetic::{Phonetic, PhoneticIndex};
+use crate::segment::Segmenter;
+
+// Options taking a value that every corrector binary accepts
+pub const VALUE_FLAGS: &[&str] = &["--suggestions", "--max-distance", "--confusion",
+                                   "--phonetic"];
+
+// Options without a value that every corrector binary accepts
+pub const SWITCH_FLAGS: &[&str] = &["--context", "--real-word", "--segment", "--merge",
+                                    "--case-sensitive", "--positions", "--fix"];
+
+pub struct Args {
+    training_file: String,
+    values: HashMap<String, String>,
+    switches: HashSet<String>,
+}
+
+impl Args {
+
+    pub fn parse<I>(args: I, value_flags: &[&str], switch_flags: &[&str]) -> Result<Args, String>
+        where I: IntoIterator<Item = String>
+    {
+        let mut positional = Vec::new();
+        let mut values = HashMap::new();
+        let mut switches = HashSet::new();
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if value_flags.contains(&&arg[..]) {
+                match args.next() {
+                    Some(value) => values.insert(arg, value),
+                    None => return Err(format!("{} requires a value", arg)),
+                };
+            } else if switch_flags.contains(&&arg[..]) {
+                switches.insert(arg);
+            } else if arg.starts_with("--") {
+                return Err(format!("Unknown option {}", arg));
+            } else {
+                positional.push(arg);
+            }
+        }
+
+        if positional.len() != 1 {
+            return Err("Pass in one argument--the name of the training file".to_string());
+        }
+        Ok(Args {
+            training_file: positional.remove(0),
+            values,
+            switches,
+        })
+    }
+
+    pub fn training_file(&self) -> &str {
+        &self.training_file
+    }
+
+    pub fn value<T: FromStr>(&self, flag: &str) -> Result<Option<T>, String> {
+        match self.values.get(flag) {
+            Some(value) => value.parse()
+                .map(Some)
+                .map_err(|_| format!("Invalid value for {}: {}", flag, value)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn switch(&self, flag: &str) -> bool {
+        self.switches.contains(flag)
+    }
+
+}
+
+// The training file, read and tokenized once for the corrector and everything
+// the options build from it
+pub struct Training {
+    document: Document,
+    case_sensitive: bool,
+}
+
+impl Training {
+
+    pub fn read(args: &Args) -> Training {
+        let f = fs::File::open(args.training_file()).expect("Error opening the training file");
+        Training::from_reader(f, args.switch("--case-sensitive"))
+            .unwrap_or_else(|e| panic!("Error reading the training file: {}", e))
+    }
+
+    pub fn from_reader<R: Read>(reader: R, case_sensitive: bool) -> Result<Training, String> {
+        Ok(Training {
+            document: Document::from_reader(reader)?,
+            case_sensitive,
+        })
+    }
+
+    // The words of the training file in order, as written with
+    // `--case-sensitive`
+    pub fn words(&self) -> Vec<String> {
+        self.document.tokens.iter().map(|t| self.word(t).to_string()).collect()
+    }
+
+    // How many times each word appears in the training file, counting case
+    // variants separately with `--case-sensitive`
+    pub fn counts(&self) -> CountTable {
+        let mut counts = CountTable::new();
+        for t in &self.document.tokens {
+            *counts.entry(self.word(t).to_string()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    // The usual casing of the words in the training file
+    pub fn casing(&self) -> Casing {
+        Casing::from_document(&self.document)
+    }
+
+    fn word<'a>(&self, token: &'a Token) -> &'a str {
+        if self.case_sensitive {
+            &token.text
+        } else {
+            &token.key
+        }
+    }
+
+}
+
+// The key token is looked up by: the word as written with
+// `--case-sensitive`, unless only a lowercase or capitalized form of it is
+// known, as with `The` at the start of a sentence or `LONDON` in capitals
+fn key<C: Corrector + ?Sized>(corrector: &C, token: &Token, case_sensitive: bool) -> String {
+    if !case_sensitive {
+        return token.key.to_string();
+    }
+    if corrector.check(&token.text) {
+        return token.text.to_string();
+    }
+    let forms = match Case::of(&token.text) {
+        Case::Lower => vec![],
+        Case::Title => vec![token.key.to_string()],
+        Case::Upper => vec![token.key.to_string(), Case::Title.apply(&token.key)],
+    };
+    forms.into_iter().find(|w| corrector.check(w)).unwrap_or_else(|| token.text.to_string())
+}
+
+// With `--case-sensitive`, the known form of a word that is only known
+// capitalized or in capitals, like `NASA` for `nasa` or `Nasa`. Case doesn't
+// count as an edit, so it is suggested at distance 0, before any other
+// correction.
+fn case_variant<C: Corrector + ?Sized>(corrector: &C, token: &Token, case_sensitive: bool)
+    -> Option<Suggestion>
+{
+    if !case_sensitive || Case::of(&token.text) == Case::Upper || corrector.check(&token.text)
+        || corrector.check(&token.key) {
+        return None;
+    }
+    let variant = [Case::Title, Case::Upper].iter()
+        .map(|case| case.apply(&token.key))
+        .find(|w| *w != token.text && corrector.check(w))?;
+    let frequency = corrector.suggest_within(&variant, 0, 1).first().map_or(0, |s| s.frequency);
+    Some(Suggestion::new(&variant, 0, frequency))
+}
+
+// The checker for the options in args, with anything it needs beyond the
+// corrector built from training
+pub fn checker<'a, C: Corrector + ?Sized>(corrector: &'a C, training: &Training, args: &Args)
+    -> Checker<'a, C>
+{
+    let mut checker = Checker::new(corrector);
+    let confusion: Option<String> = args.value("--confusion").unwrap_or_else(|e| panic!("{}", e));
+    let real_word = args.switch("--real-word") || confusion.is_some();
+    if args.switch("--context") || real_word || args.switch("--merge") {
+        checker.set_language_model(LanguageModel::from_words(&training.words()));
+    }
+    if real_word {
+        let sets = match confusion {
+            Some(path) => {
+                let f = fs::File::open(path).expect("Error opening the confusion set file");
+                ConfusionSets::from_reader(f).unwrap_or_else(|e| panic!("{}", e))
+            }
+            None => ConfusionSets::default(),
+        };
+        checker.set_confusion_sets(sets);
+    }
+    let phonetic: Option<Phonetic> = args.value("--phonetic").unwrap_or_else(|e| panic!("{}", e));
+    if args.switch("--segment") || phonetic.is_some() {
+        let counts = training.counts();
+        if let Some(phonetic) = phonetic {
+            checker.set_phonetic_index(PhoneticIndex::new(phonetic, &counts));
+        }
+        if args.switch("--segment") {
+            checker.set_segmenter(Segmenter::new(counts));
+        }
+    }
+    checker.set_merge(args.switch("--merge"));
+    checker
+}
+
+fn keys<C: Corrector + ?Sized>(corrector: &C, tokens: &[Token], args: &Args) -> Vec<String> {
+    let case_sensitive = args.switch("--case-sensitive");
+    tokens.iter().map(|t| key(corrector, t, case_sensitive)).collect()
+}
+
+// Prints the corrections for the words of document, or with `--fix`, the
+// document itself with its misspelled words corrected
+pub fn print_document<C: Corrector + ?Sized>(corrector: &C, training: &Training,
+                                             document: &Document, args: &Args) {
+    if args.switch("--fix") {
+        print!("{}", fix(corrector, training, document, args));
+    } else {
+        print_corrections(corrector, training, document, args);
+    }
+}
+
+// The document with each misspelled word replaced by its best correction,
+// and everything else as it was
+pub fn fix<C: Corrector + ?Sized>(corrector: &C, training: &Training, document: &Document,
+                                  args: &Args) -> String {
+    let checker = checker(corrector, training, args);
+    let casing = training.casing();
+    let tokens = &document.tokens;
+    let words = keys(corrector, tokens, args);
+    let case_sensitive = args.switch("--case-sensitive");
+
+    let mut replacements = Vec::new();
+    let mut i = 0;
+    while i < words.len() {
+        let written = &tokens[i].text;
+        if let Some(merged) = checker.merged(&words, i, document.gap(i)) {
+            let span = format!("{} {}", written, tokens[i + 1].text);
+            replacements.push((i..(i + 2), casing.recase(&span, &merged.word)));
+            i += 2;
+            continue;
+        }
+        let variant = case_variant(corrector, &tokens[i], case_sensitive);
+        if let Correction::Suggested { word: best, .. } = suggest(&checker, variant, &words, i) {
+            replacements.push((i..(i + 1), casing.recase(written, &best)));
+        }
+        i += 1;
+    }
+    document.rewrite(&replacements)
+}
+
+pub fn print_corrections<C: Corrector + ?Sized>(corrector: &C, training: &Training,
+                                                document: &Document, args: &Args) {
+    let suggestions: Option<usize> = args.value("--suggestions").unwrap_or_else(|e| panic!("{}", e));
+    let checker = checker(corrector, training, args);
+    let casing = training.casing();
+    let tokens = &document.tokens;
+    let words = keys(corrector, tokens, args);
+    let positions = args.switch("--positions");
+    let case_sensitive = args.switch("--case-sensitive");
+    let mut i = 0;
+    while i < words.len() {
+        // Two words joined into one are printed together
+        let (line, n) = if let Some(merged) = checker.merged(&words, i, document.gap(i)) {
+            let span = format!("{} {}", tokens[i].text, tokens[i + 1].text);
+            let merged = recase(&casing, &span, merged);
+            let line = match suggestions {
+                Some(_) => format_suggestions(&span, &[merged]),
+                None => format_correction(&span, &merged.into_correction()),
+            };
+            (line, 2)
+        } else {
+            let word = &tokens[i].text;
+            let variant = case_variant(corrector, &tokens[i], case_sensitive);
+            let line = match suggestions {
+                Some(k) => match review(&checker, variant, &words, i, k) {
+                    Some(v) => {
+                        let v: Vec<Suggestion> = v.into_iter()
+                            .map(|s| recase(&casing, word, s))
+                            .collect();
+                        format_suggestions(word, &v)
+                    }
+                    None => word.to_string(),
+                },
+                None => {
+                    let correction = match suggest(&checker, variant, &words, i) {
+                        Correction::Suggested { word: best, distance, frequency } => {
+                            let best = casing.recase(word, &best);
+                            Correction::Suggested { word: best, distance, frequency }
+                        }
+                        c => c,
+                    };
+                    format_correction(word, &correction)
+                }
+            };
+            (line, 1)
+        };
+
+        if positions {
+            println!("{}{}", format_position(&tokens[i], &tokens[i + n - 1]), line);
+        } else {
+            println!("{}", line);
+        }
+        i += n;
+    }
+}
+
+// The correction for words[i], which is variant if there is one
+fn suggest<C: Corrector + ?Sized>(checker: &Checker<C>, variant: Option<Suggestion>,
+                                  words: &[String], i: usize) -> Correction {
+    match variant {
+        Some(Suggestion { word, distance, frequency }) => {
+            Correction::Suggested { word, distance, frequency }
+        }
+        None => checker.suggest(words, i),
+    }
+}
+
+// None if words[i] passes the checker, or else up to k candidates for it,
+// starting with variant if there is one
+fn review<C: Corrector + ?Sized>(checker: &Checker<C>, variant: Option<Suggestion>,
+                                 words: &[String], i: usize, k: usize)
+    -> Option<Vec<Suggestion>>
+{
+    let variant = match variant {
+        Some(variant) => variant,
+        None => return checker.review(words, i, k),
+    };
+    let mut v: Vec<Suggestion> = checker.suggest_top_k(words, i, k).into_iter()
+        .filter(|s| s.word != variant.word)
+        .collect();
+    v.insert(0, variant);
+    v.truncate(k);
+    Some(v)
+}
+
+fn recase(casing: &Casing, written: &str, s: Suggestion) -> Suggestion {
+    Suggestion { word: casing.recase(written, &s.word), ..s }
+}
+
+// Where the words from first to last were found: the line and column of
+// first, and the byte range from its start to the end of last
+pub fn format_position(first: &Token, last: &Token) -> String {
+    format!("{}:{}:{}-{}: ", first.line, first.column, first.bytes.start, last.bytes.end)
+}
+
+pub fn format_correction(word: &str, correction: &Correction) -> String {
+    match *correction {
+        // Word spelled correctly
+        Correction::Known => word.to_string(),
+
+        // Best suggestion
+        Correction::Suggested { word: ref best, .. } => format!("{}, {}", word, best),
+
+        // No suggestions found
+        Correction::NoSuggestion => format!("{}, -", word),
+    }
+}
+
+pub fn format_suggestions(word: &str, suggestions: &[Suggestion]) -> String {
+    if suggestions.is_empty() {
+        return format!("{}, -", word);
+    }
+    let ranked: Vec<String> = suggestions.iter()
+        .map(|s| format!("{} ({}, {})", s.word, s.distance, s.frequency))
+        .collect();
+    format!("{}, {}", word, ranked.join(", "))
+}
+
+#[cfg(test)]
+mod args_tests {
+
+    use super::Args;
+
+    #[test]
+    fn reads_training_file_and_options() {
+        let args = parse(&["--suggestions", "3", "big.txt", "--fast"]).unwrap();
+        assert_eq!("big.txt", args.training_file());
+        assert_eq!(Ok(Some(3)), args.value::<usize>("--suggestions"));
+        assert!(args.switch("--fast"));
+    }
+
+    #[test]
+    fn missing_options_are_none() {
+        let args = parse(&["big.txt"]).unwrap();
+        assert_eq!(Ok(None), args.value::<usize>("--suggestions"));
+        assert!(!args.switch("--fast"));
+    }
+
+    #[test]
+    fn requires_one_training_file() {
+        assert!(parse(&[]).is_err());
+        assert!(parse(&["big.txt", "small.txt"]).is_err());
+    }
+
+    #[test]
+    fn rejects_bad_options() {
+        assert!(parse(&["big.txt", "--slow"]).is_err());
+        assert!(parse(&["big.txt", "--suggestions"]).is_err());
+        assert!(parse(&["big.txt", "--suggestions", "many"]).unwrap()
+            .value::<usize>("--suggestions").is_err());
+    }
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(|s| s.to_string()), &["--suggestions"], &["--fast"])
+    }
+
+}
+
+#[cfg(test)]
+mod training_tests {
+
+    use super::Training;
+
+    #[test]
+    fn counts_case_variants_separately() {
+        let training = Training::from_reader("US us US. London".as_bytes(), true).unwrap();
+        let h = training.counts();
+        assert_eq!(Some(&2), h.get("US"));
+        assert_eq!(Some(&1), h.get("us"));
+        assert_eq!(Some(&1), h.get("London"));
+        assert_eq!(None, h.get("london"));
+        assert_eq!(training.words(), ["US", "us", "US", "London"]);
+    }
+
+    #[test]
+    fn counts_lowercase_words() {
+        let training = Training::from_reader("US us US in London".as_bytes(), false).unwrap();
+        let h = training.counts();
+        assert_eq!(Some(&3), h.get("us"));
+        assert_eq!(Some(&1), h.get("london"));
+        assert_eq!(training.words(), ["us", "us", "us", "in", "london"]);
+        assert_eq!(training.casing().learned("london"), Some("London"));
+    }
+
+    #[test]
+    fn rejects_training_files_that_are_not_text() {
+        assert!(Training::from_reader(&b"caf\xe9"[..], false).is_err());
+    }
+
+}
+
+#[cfg(test)]
+mod format_tests {
+
+    use crate::corrector::{Correction, Suggestion};
+    use crate::input::Token;
+    use super::{format_correction, format_position, format_suggestions};
+
+    #[test]
+    fn formats_corrections() {
+        assert_eq!("bed", format_correction("bed", &Correction::Known));
+        assert_eq!("sflj, -", format_correction("sflj", &Correction::NoSuggestion));
+        let chair = Correction::Suggested { word: "chair".to_string(), distance: 1, frequency: 12 };
+        assert_eq!("qhair, chair", format_correction("qhair", &chair));
+    }
+
+    #[test]
+    fn formats_suggestions() {
+        assert_eq!("sflj, -", format_suggestions("sflj", &[]));
+        assert_eq!("qhair, chair (1, 40), hair (1, 12)",
+                   format_suggestions("qhair", &[Suggestion::new("chair", 1, 40),
+                                                 Suggestion::new("hair", 1, 12)]));
+    }
+
+    #[test]
+    fn formats_positions() {
+        let some = Token::at("some", 2, 5, 14);
+        assert_eq!("2:5:14-18: ", format_position(&some, &some));
+        assert_eq!("2:5:14-24: ", format_position(&some, &Token::at("thing", 2, 10, 19)));
+    }
+
+}
+
+#[cfg(test)]
+mod key_tests {
+
+    use crate::corrector::{Correction, Corrector, Suggestion};
+    use crate::distance::damerau_levenshtein_distance;
+    use crate::input::Token;
+    use super::{case_variant, key};
+
+    // Knows words exactly as written, and suggests those up to two edits away
+    pub struct Dictionary(pub &'static [&'static str]);
+
+    impl Corrector for Dictionary {
+        fn check(&self, word: &str) -> bool {
+            self.0.contains(&word)
+        }
+
+        fn suggest(&self, word: &str) -> Correction {
+            match self.suggest_top_k(word, 1).into_iter().next() {
+                Some(best) => best.into_correction(),
+                None => Correction::NoSuggestion,
+            }
+        }
+
+        fn suggest_top_k(&self, word: &str, k: usize) -> Vec<Suggestion> {
+            let mut v: Vec<Suggestion> = self.0.iter()
+                .map(|w| Suggestion::new(w, damerau_levenshtein_distance(word, w), 1))
+                .filter(|s| s.distance <= 2)
+                .collect();
+            v.sort_by(Suggestion::rank);
+            v.truncate(k);
+            v
+        }
+    }
+
+    const WORDS: Dictionary = Dictionary(&["the", "London", "US", "us"]);
+
+    fn key_of(text: &str) -> String {
+        key(&WORDS, &Token::new(text), true)
+    }
+
+    #[test]
+    fn keeps_case_variants_apart() {
+        assert_eq!(key_of("US"), "US");
+        assert_eq!(key_of("us"), "us");
+        assert_eq!(key_of("London"), "London");
+        assert_eq!(key_of("london"), "london");
+        assert!(!WORDS.check(&key_of("london")));
+    }
+
+    #[test]
+    fn accepts_capitalized_forms_of_known_words() {
+        assert_eq!(key_of("The"), "the");
+        assert_eq!(key_of("THE"), "the");
+        assert_eq!(key_of("LONDON"), "London");
+        assert_eq!(key_of("Us"), "us");
+        assert_eq!(key_of("Teh"), "Teh");
+    }
+
+    #[test]
+    fn lowercases_without_case_sensitivity() {
+        assert_eq!(key(&WORDS, &Token::new("US"), false), "us");
+        assert_eq!(key(&WORDS, &Token::new("London"), false), "london");
+    }
+
+    #[test]
+    fn suggests_case_variants() {
+        let words = Dictionary(&["NASA", "London", "us", "was"]);
+        let variant = |text| case_variant(&words, &Token::new(text), true);
+        assert_eq!(variant("nasa"), Some(Suggestion::new("NASA", 0, 1)));
+        assert_eq!(variant("Nasa"), Some(Suggestion::new("NASA", 0, 1)));
+        assert_eq!(variant("london"), Some(Suggestion::new("London", 0, 1)));
+        assert_eq!(variant("LONDON"), None);
+        assert_eq!(variant("us"), None);
+        assert_eq!(variant("Us"), None);
+        assert_eq!(variant("nsaa"), None);
+        assert_eq!(case_variant(&words, &Token::new("nasa"), false), None);
+    }
+
+}
+
+#[cfg(test)]
+mod fix_tests {
+
+    use crate::input::Document;
+    use super::key_tests::Dictionary;
+    use super::{fix, Args, Training, SWITCH_FLAGS, VALUE_FLAGS};
+
+    const WORDS: Dictionary = Dictionary(&["the", "chair", "aged", "some", "thing", "something",
+                                           "and", "NASA", "was"]);
+
+    fn fixed(input: &str, options: &[&str]) -> String {
+        let mut argv: Vec<String> = options.iter().map(|s| s.to_string()).collect();
+        argv.push("training.txt".to_string());
+        let args = Args::parse(argv, VALUE_FLAGS, SWITCH_FLAGS).unwrap();
+        let training = Training::from_reader(
+            "the chair . something else . some other thing and".as_bytes(),
+            args.switch("--case-sensitive")).unwrap();
+        let document = Document::from_reader(input.as_bytes()).unwrap();
+        fix(&WORDS, &training, &document, &args)
+    }
+
+    #[test]
+    fn keeps_everything_but_misspelled_words() {
+        assert_eq!(fixed("Teh qhair,  aged 3\r\n-- some. Thing!\n", &["--fix"]),
+                   "The chair,  aged 3\r\n-- some. Thing!\n");
+        assert_eq!(fixed("", &["--fix"]), "");
+    }
+
+    #[test]
+    fn merges_only_words_split_by_spaces() {
+        assert_eq!(fixed("some. Thing and SOME THING, some\nthing", &["--fix", "--merge"]),
+                   "some. Thing and SOMETHING, some\nthing");
+    }
+
+    #[test]
+    fn fixes_the_case_of_words_only_known_in_capitals() {
+        assert_eq!(fixed("nasa Nasa was", &["--fix", "--case-sensitive"]), "NASA NASA was");
+    }
+
+}
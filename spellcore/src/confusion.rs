@@ -0,0 +1,106 @@
+#![doc="
+Confusion sets are groups of real words that are easily typed in place of
+each other, like `their`, `there` and `they're`. A word in a confusion set is
+flagged when another member of its set is much more likely in its context.
+
+A confusion set file lists one set per line, with its words separated by
+spaces or commas. Blank lines are skipped.
+
+ex.
+  their, there, they're
+  then than
+"]
+
+use std::collections::HashMap;
+use std::io::Read;
+
+// Used when no confusion set file is given
+pub const DEFAULT_CONFUSION_SETS: &str = "\
+their there they're
+then than
+affect effect
+accept except
+its it's
+your you're
+whose who's
+to too two
+lose loose
+weather whether
+quiet quite
+advice advise
+principal principle
+";
+
+pub struct ConfusionSets {
+    // Maps each word to the other members of its sets
+    sets: HashMap<String, Vec<String>>,
+}
+
+impl Default for ConfusionSets {
+    fn default() -> ConfusionSets {
+        ConfusionSets::parse(DEFAULT_CONFUSION_SETS)
+    }
+}
+
+impl ConfusionSets {
+
+    pub fn parse(s: &str) -> ConfusionSets {
+        let mut sets: HashMap<String, Vec<String>> = HashMap::new();
+        for line in s.lines() {
+            let words: Vec<String> = line.split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|w| !w.is_empty())
+                .map(|w| w.to_lowercase())
+                .collect();
+            for w in &words {
+                let others = sets.entry(w.to_string()).or_default();
+                for other in &words {
+                    if other != w && !others.contains(other) {
+                        others.push(other.to_string());
+                    }
+                }
+            }
+        }
+        ConfusionSets { sets }
+    }
+
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<ConfusionSets, String> {
+        let mut s = String::new();
+        reader.read_to_string(&mut s).map_err(|e| e.to_string())?;
+        Ok(ConfusionSets::parse(&s))
+    }
+
+    // The words word is easily confused with
+    pub fn confusions(&self, word: &str) -> &[String] {
+        self.sets.get(word).map(|v| &v[..]).unwrap_or(&[])
+    }
+
+}
+
+#[cfg(test)]
+mod confusion_tests {
+
+    use super::ConfusionSets;
+
+    #[test]
+    fn parses_sets() {
+        let sets = ConfusionSets::parse("Their, there they're\n\nthen than\nthan thane\n");
+        assert_eq!(sets.confusions("their"), ["there", "they're"]);
+        assert_eq!(sets.confusions("they're"), ["their", "there"]);
+        assert_eq!(sets.confusions("than"), ["then", "thane"]);
+        assert!(sets.confusions("the").is_empty());
+    }
+
+    #[test]
+    fn reads_sets() {
+        let sets = ConfusionSets::from_reader("affect effect\n".as_bytes()).unwrap();
+        assert_eq!(sets.confusions("effect"), ["affect"]);
+    }
+
+    #[test]
+    fn has_default_sets() {
+        let sets = ConfusionSets::default();
+        assert!(sets.confusions("then").contains(&"than".to_string()));
+        assert!(sets.confusions("two").contains(&"too".to_string()));
+    }
+
+}
@@ -0,0 +1,119 @@
+#![doc="
+The `Corrector` trait is implemented by every spelling correction engine so
+that callers can switch algorithms without changing their call sites.
+
+* `check` tells whether a word is in the dictionary.
+* `suggest` returns a `Correction` describing what should happen to a word.
+* `suggest_top_k` returns up to `k` candidate corrections, best first. A known
+  word is its own best candidate.
+
+Candidates are ranked by edit distance, then by frequency in the training
+file, then alphabetically, so the order is the same on every run.
+"]
+
+use std::cmp::Ordering;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Correction {
+    // The word was found in the training file
+    Known,
+
+    // The word is misspelled and `word` is the best replacement, `distance`
+    // edits away and seen `frequency` times in the training file
+    Suggested { word: String, distance: usize, frequency: usize },
+
+    // The word is misspelled and nothing close enough was found
+    NoSuggestion,
+}
+
+impl Correction {
+
+    pub fn suggested(word: &str, distance: usize, frequency: usize) -> Correction {
+        Correction::Suggested { word: word.to_string(), distance, frequency }
+    }
+
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Suggestion {
+    pub word: String,
+    pub distance: usize,
+    pub frequency: usize,
+}
+
+impl Suggestion {
+
+    pub fn new(word: &str, distance: usize, frequency: usize) -> Suggestion {
+        Suggestion {
+            word: word.to_string(),
+            distance,
+            frequency,
+        }
+    }
+
+    // Orders suggestions best first
+    pub fn rank(&self, other: &Suggestion) -> Ordering {
+        self.distance.cmp(&other.distance)
+            .then_with(|| other.frequency.cmp(&self.frequency))
+            .then_with(|| self.word.cmp(&other.word))
+    }
+
+    // The correction for the word this was suggested for, assuming it is the
+    // best suggestion
+    pub fn into_correction(self) -> Correction {
+        if self.distance == 0 {
+            Correction::Known
+        } else {
+            Correction::Suggested {
+                word: self.word,
+                distance: self.distance,
+                frequency: self.frequency,
+            }
+        }
+    }
+}
+
+pub trait Corrector {
+    fn check(&self, word: &str) -> bool;
+
+    fn suggest(&self, word: &str) -> Correction;
+
+    fn suggest_top_k(&self, word: &str, k: usize) -> Vec<Suggestion>;
+
+    // Like suggest_top_k, but only words within max_distance of word.
+    // Correctors that can search a smaller radius faster should override it.
+    fn suggest_within(&self, word: &str, max_distance: usize, k: usize) -> Vec<Suggestion> {
+        self.suggest_top_k(word, k).into_iter()
+            .filter(|s| s.distance <= max_distance)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod suggestion_tests {
+
+    use super::{Correction, Suggestion};
+
+    #[test]
+    fn ranks_by_distance_then_frequency_then_word() {
+        let mut v = [Suggestion::new("how", 1, 1),
+                     Suggestion::new("why", 2, 9),
+                     Suggestion::new("hoe", 1, 1),
+                     Suggestion::new("who", 1, 2)];
+        v.sort_by(Suggestion::rank);
+        let words: Vec<&str> = v.iter().map(|s| &s.word[..]).collect();
+        assert_eq!(vec!["who", "hoe", "how", "why"], words);
+    }
+
+    #[test]
+    fn exact_match_is_known() {
+        assert_eq!(Correction::Known, Suggestion::new("who", 0, 2).into_correction());
+    }
+
+    #[test]
+    fn close_match_is_suggested() {
+        assert_eq!(Correction::Suggested { word: "who".to_string(), distance: 1, frequency: 2 },
+                   Suggestion::new("who", 1, 2).into_correction());
+    }
+
+}
@@ -0,0 +1,606 @@
+#![doc="
+Distances between words, measured as the number of single letter edits it
+takes to turn one word into the other.
+
+* `levenshtein_distance` counts insertions, deletions and substitutions.
+* `damerau_levenshtein_distance` also counts a transposition of two adjacent
+  letters as one edit, matching the edits used by the Norvig corrector.
+* `unrestricted_damerau_levenshtein_distance` also lets transposed letters be
+  edited again. Unlike the restricted distance it satisfies the triangle
+  inequality, so it can weight a BK tree.
+
+A letter is a Unicode scalar value (a `char`). With the `graphemes` feature,
+the `grapheme_*` variants count grapheme clusters instead, so that a base
+letter followed by combining accents is a single letter. The generic
+`levenshtein` and `damerau_levenshtein` work on slices of any comparable
+items.
+
+The `_within` variants take a limit and return `None` as soon as the distance
+is known to exceed it, which is much cheaper than computing a large distance
+exactly. Only two rows of the distance matrix are kept (three for
+Damerau-Levenshtein), on the stack for all but very long words.
+
+`MaxDistance` decides how far from a word correctors search for candidates.
+"]
+
+use std::cmp::{max, min};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::mem::swap;
+use std::str::FromStr;
+
+#[cfg(feature = "graphemes")]
+use unicode_segmentation::UnicodeSegmentation;
+
+// Rows of the distance matrix are kept on the stack when they fit in this many
+// cells, which covers all but the longest words
+const STACK_CELLS: usize = 192;
+
+// Distances are counted in Unicode scalar values (chars), so accented letters
+// like the 'é' in 'café' count as a single letter.
+pub fn levenshtein_distance(w1: &str, w2: &str) -> usize {
+    levenshtein_distance_within(w1, w2, usize::MAX).expect("Impossible")
+}
+
+// The Levenshtein distance if it is at most limit, otherwise None. Gives up as
+// soon as every alignment costs more than limit.
+pub fn levenshtein_distance_within(w1: &str, w2: &str, limit: usize) -> Option<usize> {
+    if w1.is_ascii() && w2.is_ascii() {
+        levenshtein_within(w1.as_bytes(), w2.as_bytes(), limit)
+    } else {
+        let w1: Vec<char> = w1.chars().collect();
+        let w2: Vec<char> = w2.chars().collect();
+        levenshtein_within(&w1, &w2, limit)
+    }
+}
+
+// Restricted Damerau-Levenshtein (optimal string alignment) distance: like the
+// Levenshtein distance, but swapping two adjacent letters counts as a single
+// edit, as it does in the Norvig corrector's edit model. No substring is edited
+// more than once, so 'ca' -> 'abc' is 3 rather than 2.
+pub fn damerau_levenshtein_distance(w1: &str, w2: &str) -> usize {
+    damerau_levenshtein_distance_within(w1, w2, usize::MAX).expect("Impossible")
+}
+
+// The restricted Damerau-Levenshtein distance if it is at most limit,
+// otherwise None
+pub fn damerau_levenshtein_distance_within(w1: &str, w2: &str, limit: usize) -> Option<usize> {
+    if w1.is_ascii() && w2.is_ascii() {
+        damerau_levenshtein_within(w1.as_bytes(), w2.as_bytes(), limit)
+    } else {
+        let w1: Vec<char> = w1.chars().collect();
+        let w2: Vec<char> = w2.chars().collect();
+        damerau_levenshtein_within(&w1, &w2, limit)
+    }
+}
+
+// Unrestricted Damerau-Levenshtein distance: like the restricted distance, but
+// letters can be edited again after being transposed, so 'ca' -> 'abc' is 2.
+// It is never more than the restricted distance.
+pub fn unrestricted_damerau_levenshtein_distance(w1: &str, w2: &str) -> usize {
+    let w1: Vec<char> = w1.chars().collect();
+    let w2: Vec<char> = w2.chars().collect();
+    unrestricted_damerau_levenshtein(&w1, &w2)
+}
+
+// Like `levenshtein_distance`, but counted in grapheme clusters, so a letter
+// followed by combining accents is a single letter.
+#[cfg(feature = "graphemes")]
+pub fn grapheme_levenshtein_distance(w1: &str, w2: &str) -> usize {
+    let w1: Vec<&str> = w1.graphemes(true).collect();
+    let w2: Vec<&str> = w2.graphemes(true).collect();
+    levenshtein(&w1, &w2)
+}
+
+// Like `damerau_levenshtein_distance`, but counted in grapheme clusters.
+#[cfg(feature = "graphemes")]
+pub fn grapheme_damerau_levenshtein_distance(w1: &str, w2: &str) -> usize {
+    let w1: Vec<&str> = w1.graphemes(true).collect();
+    let w2: Vec<&str> = w2.graphemes(true).collect();
+    damerau_levenshtein(&w1, &w2)
+}
+
+// Like `unrestricted_damerau_levenshtein_distance`, but counted in grapheme
+// clusters.
+#[cfg(feature = "graphemes")]
+pub fn grapheme_unrestricted_damerau_levenshtein_distance(w1: &str, w2: &str) -> usize {
+    let w1: Vec<&str> = w1.graphemes(true).collect();
+    let w2: Vec<&str> = w2.graphemes(true).collect();
+    unrestricted_damerau_levenshtein(&w1, &w2)
+}
+
+pub fn levenshtein<E: PartialEq>(w1: &[E], w2: &[E]) -> usize {
+    levenshtein_within(w1, w2, usize::MAX).expect("Impossible")
+}
+
+pub fn levenshtein_within<E: PartialEq>(w1: &[E], w2: &[E], limit: usize) -> Option<usize> {
+    with_rows(2, w2.len() + 1, |rows| {
+        let (prev, cur) = rows.split_at_mut(w2.len() + 1);
+        bounded_distance(w1, w2, limit, prev, cur, None)
+    })
+}
+
+pub fn damerau_levenshtein<E: PartialEq>(w1: &[E], w2: &[E]) -> usize {
+    damerau_levenshtein_within(w1, w2, usize::MAX).expect("Impossible")
+}
+
+pub fn damerau_levenshtein_within<E: PartialEq>(w1: &[E], w2: &[E], limit: usize) -> Option<usize> {
+    with_rows(3, w2.len() + 1, |rows| {
+        let (prev, rest) = rows.split_at_mut(w2.len() + 1);
+        let (cur, prev2) = rest.split_at_mut(w2.len() + 1);
+        bounded_distance(w1, w2, limit, prev, cur, Some(prev2))
+    })
+}
+
+// Keeps the whole distance matrix, with an extra first row and column, since a
+// transposition can reach back to any earlier row
+pub fn unrestricted_damerau_levenshtein<E: Eq + Hash>(w1: &[E], w2: &[E]) -> usize {
+    let (len1, len2) = (w1.len(), w2.len());
+    let cols = len2 + 2;
+    let far = len1 + len2;
+    let mut d = vec![0; (len1 + 2) * cols];
+    d[0] = far;
+    for i in 0..(len1+1) {
+        d[(i+1) * cols] = far;
+        d[(i+1) * cols + 1] = i;
+    }
+    for j in 0..(len2+1) {
+        d[j+1] = far;
+        d[cols + j + 1] = j;
+    }
+
+    // The last row each letter of w1 was seen in
+    let mut last_row: HashMap<&E, usize> = HashMap::new();
+    for i in 1..(len1+1) {
+        // The last column in this row where the letters matched
+        let mut last_col = 0;
+        for j in 1..(len2+1) {
+            let i1 = last_row.get(&w2[j-1]).cloned().unwrap_or(0);
+            let j1 = last_col;
+            let same = if w1[i-1] == w2[j-1] {
+                last_col = j;
+                0
+            } else {
+                1
+            };
+            let dist = min(min(d[i*cols + j] + same, d[(i+1)*cols + j] + 1),
+                           d[i*cols + j + 1] + 1);
+
+            // Transposition of w1[i1-1] and w1[i-1], with everything between
+            // them deleted and everything between w2[j1-1] and w2[j-1] inserted
+            let transposed = d[i1*cols + j1] + (i - i1 - 1) + 1 + (j - j1 - 1);
+            d[(i+1)*cols + j + 1] = min(dist, transposed);
+        }
+        last_row.insert(&w1[i-1], i);
+    }
+    d[(len1+1)*cols + len2 + 1]
+}
+
+// Calls f with count rows of len cells each, on the stack if they fit
+fn with_rows<R, F>(count: usize, len: usize, f: F) -> R
+    where F: FnOnce(&mut [usize]) -> R
+{
+    if count * len <= STACK_CELLS {
+        let mut cells = [0; STACK_CELLS];
+        f(&mut cells[..(count * len)])
+    } else {
+        f(&mut vec![0; count * len])
+    }
+}
+
+// Computes the distance matrix of w1 and w2 one row at a time, keeping only
+// the last two rows (three with transpositions). Only cells within limit of
+// the diagonal can hold a distance of at most limit, so the rest are skipped,
+// and the search stops as soon as a whole row exceeds limit. Distances above
+// limit are all stored as limit + 1.
+fn bounded_distance<'a, E: PartialEq>(w1: &[E],
+                                      w2: &[E],
+                                      limit: usize,
+                                      mut prev: &'a mut [usize],
+                                      mut cur: &'a mut [usize],
+                                      mut prev2: Option<&'a mut [usize]>)
+                                      -> Option<usize> {
+    let len1 = w1.len();
+    let len2 = w2.len();
+
+    // The distance is at least the difference in length and at most the length
+    // of the longer word
+    if max(len1, len2) - min(len1, len2) > limit {
+        return None;
+    }
+    let limit = min(limit, max(len1, len2));
+    let over = limit + 1;
+
+    for (j, cell) in prev.iter_mut().enumerate() {
+        *cell = min(j, over);
+    }
+
+    for i in 1..(len1+1) {
+        let lo = max(1, i.saturating_sub(limit));
+        let hi = min(len2, i + limit);
+
+        cur[0] = min(i, over);
+        if lo > 1 {
+            cur[lo-1] = over;
+        }
+        let mut row_min = cur[0];
+
+        for j in lo..(hi+1) {
+            let same = if w1[i-1] == w2[j-1] { 0 } else { 1 };
+            let mut dist = min(min(prev[j]+1, cur[j-1]+1), prev[j-1]+same);
+
+            // Transposition of adjacent letters
+            if let Some(ref prev2) = prev2 {
+                if i > 1 && j > 1 && w1[i-1] == w2[j-2] && w1[i-2] == w2[j-1] {
+                    dist = min(dist, prev2[j-2]+1);
+                }
+            }
+
+            cur[j] = min(dist, over);
+            row_min = min(row_min, cur[j]);
+        }
+        if hi < len2 {
+            cur[hi+1] = over;
+        }
+
+        if row_min > limit {
+            return None;
+        }
+
+        match prev2 {
+            Some(ref mut prev2) => {
+                swap(prev2, &mut prev);
+                swap(&mut prev, &mut cur);
+            }
+            None => swap(&mut prev, &mut cur),
+        }
+    }
+
+    if prev[len2] <= limit {
+        Some(prev[len2])
+    } else {
+        None
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MaxDistance {
+    // The same limit for every word
+    Fixed(usize),
+
+    // 1 for words of up to 4 letters, 2 for words of up to 12 letters and 3 for
+    // longer words, so short words don't match unrelated words and long words
+    // can still be corrected
+    Adaptive,
+
+    // A fraction of the word's length, rounded down but at least 1
+    Ratio(f64),
+}
+
+impl MaxDistance {
+
+    pub fn for_word(&self, word: &str) -> usize {
+        match *self {
+            MaxDistance::Fixed(d) => d,
+            MaxDistance::Adaptive => match word.chars().count() {
+                0..=4 => 1,
+                5..=12 => 2,
+                _ => 3,
+            },
+            MaxDistance::Ratio(r) => max(1, (word.chars().count() as f64 * r) as usize),
+        }
+    }
+
+    // The limit for word when no known word is longer than longest chars. No
+    // word can be further from word than the longer of the two is long, so a
+    // larger limit would find nothing more
+    pub fn within(&self, word: &str, longest: usize) -> usize {
+        min(self.for_word(word), max(word.chars().count(), longest))
+    }
+
+}
+
+// Parses a number like "2", "adaptive" or a percentage like "25%"
+impl FromStr for MaxDistance {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<MaxDistance, String> {
+        if s == "adaptive" {
+            return Ok(MaxDistance::Adaptive);
+        }
+        if let Some(percent) = s.strip_suffix('%') {
+            return match percent.parse::<f64>() {
+                Ok(p) if p.is_finite() && p >= 0.0 => Ok(MaxDistance::Ratio(p / 100.0)),
+                _ => Err(format!("Invalid maximum distance {}", s)),
+            };
+        }
+        s.parse()
+            .map(MaxDistance::Fixed)
+            .map_err(|_| format!("Invalid maximum distance {}", s))
+    }
+}
+
+#[cfg(test)]
+mod levenshtein_distance_tests {
+
+    use super::levenshtein_distance;
+
+    #[test]
+    fn test_hello_world() {
+        assert_eq!(levenshtein_distance("hello", "world"), 4);
+    }
+
+    #[test]
+    fn test_cat_hat() {
+        assert_eq!(levenshtein_distance("cat", "hat"), 1);
+    }
+
+    #[test]
+    fn test_book_back() {
+        assert_eq!(levenshtein_distance("book", "back"), 2);
+    }
+
+    #[test]
+    fn test_world_wordl() {
+        assert_eq!(levenshtein_distance("world", "wordl"), 2);
+    }
+
+    #[test]
+    fn test_lion_lying() {
+        assert_eq!(levenshtein_distance("lion", "lying"), 3);
+    }
+
+    #[test]
+    fn test_astrology_astronomy() {
+        assert_eq!(levenshtein_distance("astrology", "astronomy"), 2);
+    }
+
+    #[test]
+    fn test_books_slack() {
+        assert_eq!(levenshtein_distance("books", "slack"), 5);
+    }
+
+    #[test]
+    fn test_computer_him() {
+        assert_eq!(levenshtein_distance("computer", "him"), 7);
+    }
+
+    #[test]
+    fn test_elephant_elegant() {
+        assert_eq!(levenshtein_distance("elephant", "elegant"), 2);
+    }
+
+    #[test]
+    fn test_onomatopoeia_hyperbole() {
+        assert_eq!(levenshtein_distance("onomatopoeia", "hyperbole"), 10);
+    }
+
+    #[test]
+    fn test_cafe_cafe_accented() {
+        assert_eq!(levenshtein_distance("café", "cafe"), 1);
+    }
+
+    #[test]
+    fn test_naive_naive_accented() {
+        assert_eq!(levenshtein_distance("naïve", "naive"), 1);
+    }
+
+    #[test]
+    fn test_strasse_strasse_sharp_s() {
+        assert_eq!(levenshtein_distance("straße", "strasse"), 2);
+    }
+
+    #[test]
+    fn test_uber_ober() {
+        assert_eq!(levenshtein_distance("über", "öber"), 1);
+    }
+    
+}
+
+#[cfg(test)]
+mod damerau_levenshtein_distance_tests {
+
+    use super::{damerau_levenshtein_distance, unrestricted_damerau_levenshtein_distance};
+
+    #[test]
+    fn test_world_wordl() {
+        assert_eq!(damerau_levenshtein_distance("world", "wordl"), 1);
+    }
+
+    #[test]
+    fn test_fera_fear() {
+        assert_eq!(damerau_levenshtein_distance("fera", "fear"), 1);
+    }
+
+    #[test]
+    fn test_hello_world() {
+        assert_eq!(damerau_levenshtein_distance("hello", "world"), 4);
+    }
+
+    #[test]
+    fn test_cat_hat() {
+        assert_eq!(damerau_levenshtein_distance("cat", "hat"), 1);
+    }
+
+    #[test]
+    fn test_abcdef_badcfe() {
+        assert_eq!(damerau_levenshtein_distance("abcdef", "badcfe"), 3);
+    }
+
+    #[test]
+    fn test_ca_abc() {
+        // Optimal string alignment doesn't edit the transposed letters again
+        assert_eq!(damerau_levenshtein_distance("ca", "abc"), 3);
+        assert_eq!(unrestricted_damerau_levenshtein_distance("ca", "abc"), 2);
+    }
+
+    #[test]
+    fn test_unrestricted() {
+        for (w1, w2) in &[("world", "wordl"), ("hello", "world"), ("abcdef", "badcfe"),
+                          ("ac", "ca"), ("", "abc"), ("abc", ""), ("niño", "inño")] {
+            assert_eq!(unrestricted_damerau_levenshtein_distance(w1, w2),
+                       damerau_levenshtein_distance(w1, w2), "{} {}", w1, w2);
+        }
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(damerau_levenshtein_distance("", "abc"), 3);
+        assert_eq!(damerau_levenshtein_distance("abc", ""), 3);
+    }
+
+    #[test]
+    fn test_accented_transposition() {
+        assert_eq!(damerau_levenshtein_distance("niño", "nińo"), 1);
+        assert_eq!(damerau_levenshtein_distance("niño", "inño"), 1);
+    }
+
+}
+
+#[cfg(all(test, feature = "graphemes"))]
+mod grapheme_distance_tests {
+
+    use super::{grapheme_damerau_levenshtein_distance, grapheme_levenshtein_distance,
+                levenshtein_distance};
+
+    #[test]
+    fn test_combining_accents_are_one_letter() {
+        // e followed by a combining acute and a combining circumflex
+        assert_eq!(levenshtein_distance("cafe\u{301}\u{302}", "cafe"), 2);
+        assert_eq!(grapheme_levenshtein_distance("cafe\u{301}\u{302}", "cafe"), 1);
+    }
+
+    #[test]
+    fn test_transposed_clusters() {
+        assert_eq!(grapheme_damerau_levenshtein_distance("ne\u{301}a", "nae\u{301}"), 1);
+    }
+
+}
+
+#[cfg(test)]
+mod bounded_distance_tests {
+
+    use std::cmp::min;
+    use super::{damerau_levenshtein_distance, damerau_levenshtein_distance_within,
+                levenshtein_distance, levenshtein_distance_within};
+
+    const WORDS: &[&str] = &["", "a", "ab", "ba", "abc", "ca", "hello", "world", "wordl",
+                             "book", "back", "books", "slack", "lion", "lying",
+                             "astrology", "astronomy", "computer", "him", "elephant",
+                             "elegant", "onomatopoeia", "hyperbole", "café", "cafe",
+                             "naïve", "straße", "strasse", "abcdefghijklmnopqrstuvwxyz",
+                             "zyxwvutsrqponmlkjihgfedcbazyxwvutsrqponmlkjihgfedcbazyxwvut\
+                              srqponmlkjihgfedcbazyxwvutsrqponmlkjihgfedcba"];
+
+    #[test]
+    fn levenshtein_within_limit_matches_full_matrix() {
+        for w1 in WORDS {
+            for w2 in WORDS {
+                let expected = naive(w1, w2, false);
+                assert_eq!(expected, levenshtein_distance(w1, w2), "{} {}", w1, w2);
+                for limit in 0..12 {
+                    let within = if expected <= limit { Some(expected) } else { None };
+                    assert_eq!(within, levenshtein_distance_within(w1, w2, limit),
+                               "{} {} {}", w1, w2, limit);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn damerau_levenshtein_within_limit_matches_full_matrix() {
+        for w1 in WORDS {
+            for w2 in WORDS {
+                let expected = naive(w1, w2, true);
+                assert_eq!(expected, damerau_levenshtein_distance(w1, w2), "{} {}", w1, w2);
+                for limit in 0..12 {
+                    let within = if expected <= limit { Some(expected) } else { None };
+                    assert_eq!(within, damerau_levenshtein_distance_within(w1, w2, limit),
+                               "{} {} {}", w1, w2, limit);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn stops_early_on_different_lengths() {
+        assert_eq!(None, levenshtein_distance_within("a", "abcdefghijklmnopqrstuvwxyz", 5));
+    }
+
+    // Full distance matrix, as a reference
+    fn naive(w1: &str, w2: &str, transpositions: bool) -> usize {
+        let w1: Vec<char> = w1.chars().collect();
+        let w2: Vec<char> = w2.chars().collect();
+        let mut d = vec![vec![0; w2.len()+1]; w1.len()+1];
+        for (i, row) in d.iter_mut().enumerate() {
+            row[0] = i;
+        }
+        for (j, cell) in d[0].iter_mut().enumerate() {
+            *cell = j;
+        }
+        for i in 1..(w1.len()+1) {
+            for j in 1..(w2.len()+1) {
+                let same = if w1[i-1] == w2[j-1] { 0 } else { 1 };
+                d[i][j] = min(min(d[i-1][j]+1, d[i][j-1]+1), d[i-1][j-1]+same);
+                if transpositions && i > 1 && j > 1
+                    && w1[i-1] == w2[j-2] && w1[i-2] == w2[j-1] {
+                    d[i][j] = min(d[i][j], d[i-2][j-2]+1);
+                }
+            }
+        }
+        d[w1.len()][w2.len()]
+    }
+
+}
+
+#[cfg(test)]
+mod max_distance_tests {
+
+    use super::MaxDistance;
+
+    #[test]
+    fn fixed_is_the_same_for_every_word() {
+        assert_eq!(3, MaxDistance::Fixed(3).for_word("ho"));
+        assert_eq!(3, MaxDistance::Fixed(3).for_word("electroencephalogram"));
+    }
+
+    #[test]
+    fn adaptive_grows_with_word_length() {
+        assert_eq!(1, MaxDistance::Adaptive.for_word("ho"));
+        assert_eq!(1, MaxDistance::Adaptive.for_word("niño"));
+        assert_eq!(2, MaxDistance::Adaptive.for_word("spelling"));
+        assert_eq!(3, MaxDistance::Adaptive.for_word("electroencephalogram"));
+    }
+
+    #[test]
+    fn ratio_is_a_fraction_of_word_length() {
+        let quarter = MaxDistance::Ratio(0.25);
+        assert_eq!(1, quarter.for_word("ho"));
+        assert_eq!(1, quarter.for_word("where"));
+        assert_eq!(2, quarter.for_word("spelling"));
+        assert_eq!(1, quarter.for_word("straßen"));
+        assert_eq!(5, quarter.for_word("electroencephalogram"));
+    }
+
+    #[test]
+    fn parses_from_str() {
+        assert_eq!(Ok(MaxDistance::Fixed(2)), "2".parse());
+        assert_eq!(Ok(MaxDistance::Adaptive), "adaptive".parse());
+        assert_eq!(Ok(MaxDistance::Ratio(0.25)), "25%".parse());
+        assert!("two".parse::<MaxDistance>().is_err());
+        assert!("-5%".parse::<MaxDistance>().is_err());
+        assert!("%".parse::<MaxDistance>().is_err());
+        assert!("inf%".parse::<MaxDistance>().is_err());
+        assert!("NaN%".parse::<MaxDistance>().is_err());
+    }
+
+    #[test]
+    fn is_capped_by_the_longest_word() {
+        let huge = MaxDistance::Fixed(usize::MAX);
+        assert_eq!(9, huge.within("ho", 9));
+        assert_eq!(20, huge.within("electroencephalogram", 9));
+        assert_eq!(2, MaxDistance::Fixed(2).within("ho", 9));
+        assert_eq!(1, MaxDistance::Ratio(1e300).within("h", 1));
+    }
+
+}
@@ -0,0 +1,373 @@
+#![doc="
+* Words do not include any characters except alphabetic characters and
+  apostrophes and periods (to allow acronyms).
+* The program is not case sensitive (all words are looked up in lowercase).
+  Thus, `Hello` and `hello` count as the same word. `read_tokens` keeps the
+  word as written alongside its lowercase key, so output can keep its casing
+  and `US` and `us` can be counted separately.
+* Numbers are not words.
+* Apostrophes are part of words as long as they are not at the beginning or
+  end. 
+* Periods are trimmed from the beginning and end of words. Abbreviations like
+  `etc.` will be counted as just `etc`.
+  Acronyms separated by periods will have the last period removed
+  (ex. `E.E.C.S.` would show up in the output as `e.e.c.s`).
+* Each token from `read_tokens` also records its line and column and its byte
+  range in the input, so corrections can point at where the word was found.
+* A `Document` keeps the whole input along with its tokens, so the text
+  between the words, like whitespace, punctuation and numbers, can be written
+  back out unchanged with some words replaced.
+* Combining marks, like the diaeresis of a decomposed (NFD) `naïve`, stay in
+  the word with the letter before them. With the `graphemes` feature, words
+  are split between grapheme clusters, so a letter keeps every mark combined
+  with it.
+* Hyphenated words are split into separate words. Thus, `good-looking` would
+  separate into `good` and `looking`.
+"]
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read};
+use std::ops::Range;
+
+#[cfg(feature = "graphemes")]
+use unicode_segmentation::UnicodeSegmentation;
+
+pub type CountTable = HashMap<String, usize>;
+
+pub fn read_and_count<R: Read>(map: &mut CountTable, reader: R) {
+    let v: Vec<String> = read_input(reader);
+    for w in v {
+        increment_word(map, w);
+    }
+}
+
+// A word as it was written, the lowercase key it is looked up by, and where
+// it was found: its line and column, counted in chars from 1, and its byte
+// range in the whole input
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token {
+    pub text: String,
+    pub key: String,
+    pub line: usize,
+    pub column: usize,
+    pub bytes: Range<usize>,
+}
+
+impl Token {
+
+    // A token at the very start of its input
+    pub fn new(text: &str) -> Token {
+        Token::at(text, 1, 1, 0)
+    }
+
+    pub fn at(text: &str, line: usize, column: usize, start: usize) -> Token {
+        Token {
+            text: text.to_string(),
+            key: text.to_lowercase(),
+            line,
+            column,
+            bytes: start..(start + text.len()),
+        }
+    }
+
+}
+
+// The whole text of an input and its tokens, with byte ranges into the text
+pub struct Document {
+    pub text: String,
+    pub tokens: Vec<Token>,
+}
+
+impl Document {
+
+    // Input that isn't valid UTF-8 is rejected rather than changed, so the
+    // text is written back out exactly as it was read and token byte ranges
+    // point into the input itself
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Document, String> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+        let text = String::from_utf8(bytes)
+            .map_err(|e| format!("Input is not valid UTF-8: {}", e.utf8_error()))?;
+        let tokens = read_tokens(text.as_bytes());
+        Ok(Document { text, tokens })
+    }
+
+    // The text between tokens[i] and the token after it, or the rest of the
+    // text after the last token
+    pub fn gap(&self, i: usize) -> &str {
+        let end = self.tokens.get(i + 1).map_or(self.text.len(), |t| t.bytes.start);
+        &self.text[self.tokens[i].bytes.end..end]
+    }
+
+    // The text with each range of tokens replaced by its replacement, and
+    // everything else as it was. Replacements must be in order and not
+    // overlap
+    pub fn rewrite(&self, replacements: &[(Range<usize>, String)]) -> String {
+        let mut out = String::new();
+
+        // Bytes of the text already copied or replaced
+        let mut copied = 0;
+        for (tokens, replacement) in replacements {
+            out.push_str(&self.text[copied..self.tokens[tokens.start].bytes.start]);
+            out.push_str(replacement);
+            copied = self.tokens[tokens.end - 1].bytes.end;
+        }
+        out.push_str(&self.text[copied..]);
+        out
+    }
+
+}
+
+pub fn read_input<R: Read>(reader: R) -> Vec<String> {
+    read_tokens(reader).into_iter().map(|t| t.key).collect()
+}
+
+pub fn read_tokens<R: Read>(reader: R) -> Vec<Token> {
+    let mut v = Vec::new();
+    let mut reader = BufReader::new(reader);
+    let mut line = String::new();
+    let mut line_number = 0;
+
+    // Bytes read before the current line
+    let mut offset = 0;
+    while let Ok(n) = reader.read_line(&mut line) {
+        if n == 0 {
+            break;
+        }
+        line_number += 1;
+        let tmp = split_words(&line);
+
+        // Chars before the byte counted up to, so columns are counted once
+        // per line rather than once per word
+        let (mut counted, mut chars) = (0, 0);
+        for elem in tmp {
+            // Remove leading and trailing apostrophes and periods
+            let trimmed = elem.trim_start_matches(['\'', '.']);
+            let word = trimmed.trim_end_matches(['\'', '.']);
+
+            if !word.is_empty() {
+                let start = trimmed.as_ptr() as usize - line.as_ptr() as usize;
+                chars += line[counted..start].chars().count();
+                counted = start;
+                v.push(Token::at(word, line_number, chars + 1, offset + start));
+            }
+        }
+        offset += n;
+        line.clear();
+    }
+    v
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphabetic() || c == '\'' || c == '.'
+}
+
+// Combining diacritical marks, which aren't alphabetic but belong to the
+// letter before them
+#[cfg(not(feature = "graphemes"))]
+fn is_combining_mark(c: char) -> bool {
+    matches!(c, '\u{300}'..='\u{36f}' | '\u{1ab0}'..='\u{1aff}' | '\u{1dc0}'..='\u{1dff}'
+                | '\u{20d0}'..='\u{20ff}' | '\u{fe20}'..='\u{fe2f}')
+}
+
+// The pieces of line between chars that can't be in a word
+#[cfg(not(feature = "graphemes"))]
+fn split_words(line: &str) -> Vec<&str> {
+    let mut in_word = false;
+    line.split(|c: char| {
+        in_word = is_word_char(c) || (in_word && is_combining_mark(c));
+        !in_word
+    }).collect()
+}
+
+// The pieces of line between grapheme clusters that can't be in a word
+#[cfg(feature = "graphemes")]
+fn split_words(line: &str) -> Vec<&str> {
+    let mut v = Vec::new();
+    let mut start = 0;
+    for (i, g) in line.grapheme_indices(true) {
+        if !g.starts_with(is_word_char) {
+            v.push(&line[start..i]);
+            start = i + g.len();
+        }
+    }
+    v.push(&line[start..]);
+    v
+}
+
+fn increment_word(map: &mut CountTable, word: String) {
+    *map.entry(word).or_insert(0) += 1;
+}
+
+#[cfg(test)]
+mod read_input_tests {
+    use super::{read_input, read_tokens, Document, Token};
+    use std::io::{Read, Result};
+
+    #[test]
+    fn reads_three_words_on_separate_lines() {
+        assert_read(&["hi", "hello", "hey"], "'...''hi...\nhello\nhey\n");
+    }
+
+    #[test]
+    fn reads_three_words_on_same_line() {
+        assert_read(&["hi", "hello", "hey"], "hi hello hey\n");
+    }
+
+    #[test]
+    fn trims_beginning_and_trailing_apostrophes_and_periods() {
+        assert_read(&["hi", "hello", "hey"],
+            "'...''hi...\n''...'..'hello...'\n'..'..'.hey\n");
+    }
+
+    #[test]
+    fn handles_acronyms() {
+        assert_read(&["i", "am", "a", "student", "in", "the", "e.e.c.s", "department"],
+            "I am a student \nin the E.E.C.S. department!!\n");
+    }
+
+    #[test]
+    fn splits_on_invalid_chars() {
+        assert_read(&["hi", "my", "name", "is", "kevin", "i", "don't", "like", "the", "one"],
+            "hi8 my name&is Kevin. I don't like the # 3.14159. One=1.")
+    }
+
+    #[test]
+    fn splits_on_invalid_chars_multi_line() {
+        assert_read(&["hi", "my", "name", "is", "kevin", "i", "don't", "like", "the", "one"],
+            "hi8 my\nname&is Kevin!!!!\n I don't\nlike the # 3. One=1.\n%$#^$^")
+    }
+
+    #[test]
+    fn keeps_combining_marks_in_words() {
+        assert_read(&["nai\u{308}ve", "cafe\u{301}", "x"], "Nai\u{308}ve cafe\u{301}. \u{301}x\n");
+        assert_eq!(read_tokens("e\u{301}te\u{301} 1".as_bytes())[0].text, "e\u{301}te\u{301}");
+    }
+
+    #[test]
+    fn keeps_words_as_written() {
+        let v = read_tokens(StringReader::new("Teh NASA's E.E.C.S. dept.".to_string()));
+        let written: Vec<&str> = v.iter().map(|t| &t.text[..]).collect();
+        assert_eq!(written, ["Teh", "NASA's", "E.E.C.S", "dept"]);
+        assert_eq!(v[1].key, "nasa's");
+    }
+
+    #[test]
+    fn finds_where_words_are() {
+        let input = "'Teh' café\r\n\nnaïve fox, and  E.E.C.S.\n";
+        let v = read_tokens(StringReader::new(input.to_string()));
+        assert_eq!(v, vec![Token::at("Teh", 1, 2, 1), Token::at("café", 1, 7, 6),
+                           Token::at("naïve", 3, 1, 14), Token::at("fox", 3, 7, 21),
+                           Token::at("and", 3, 12, 26), Token::at("E.E.C.S", 3, 17, 31)]);
+        assert_eq!(&input[v[1].bytes.clone()], "café");
+        assert_eq!(&input[v[2].bytes.clone()], "naïve");
+        assert_eq!(&input[v[5].bytes.clone()], "E.E.C.S");
+    }
+
+    #[test]
+    fn rewrites_documents() {
+        let input = "Teh cat, aged 3, sat\r\non teh  mat.\n\n-- some thing\n";
+        let document = Document::from_reader(input.as_bytes()).unwrap();
+        assert_eq!(document.text, input);
+        assert_eq!(document.tokens.len(), 9);
+        assert_eq!(document.rewrite(&[]), input);
+        assert_eq!(document.gap(0), " ");
+        assert_eq!(document.gap(3), "\r\n");
+        assert_eq!(document.gap(6), ".\n\n-- ");
+        assert_eq!(document.gap(8), "\n");
+        let fixed = document.rewrite(&[(0..1, "The".to_string()), (5..6, "the".to_string()),
+                                       (7..9, "something".to_string())]);
+        assert_eq!(fixed, "The cat, aged 3, sat\r\non the  mat.\n\n-- something\n");
+    }
+
+    #[test]
+    fn rejects_documents_that_are_not_text() {
+        let err = Document::from_reader(&b"caf\xe9 qhair\n"[..]).err().unwrap();
+        assert_eq!(err,
+                   "Input is not valid UTF-8: invalid utf-8 sequence of 1 bytes from index 3");
+        assert!(Document::from_reader(&[0x66, 0xff, 0x6f][..]).is_err());
+        assert!(Document::from_reader("café qhair\n".as_bytes()).is_ok());
+    }
+
+    fn assert_read(expected: &[&str], input: &str) {
+        let mock_read = StringReader::new(input.to_string());
+        let v = read_input(mock_read);
+        assert_eq!(expected.len(), v.len());
+        for i in 0..(v.len()) {
+            assert_eq!(expected[i], v[i]);
+        }
+    }
+    
+    struct StringReader {
+        contents: Vec<u8>,
+        position: usize,
+    }
+
+    impl StringReader {
+        fn new(s: String) -> Self {
+            StringReader {
+                contents: s.into_bytes(),
+                position: 0,
+            }
+        }
+    }
+
+    impl Read for StringReader {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            let mut count = 0;
+            while self.position < self.contents.len() && count < buf.len() {
+                buf[count] = self.contents[self.position];
+                count += 1;
+                self.position += 1;
+            }
+            Ok(count)
+        }
+    }
+}
+
+#[cfg(test)]
+mod increment_word_tests {
+    use super::increment_word;
+    use super::CountTable;
+ 
+    #[test]
+    fn insert_if_empty() {
+        let mut h = CountTable::new();
+        increment_word(&mut h, "one".to_string());
+
+        assert_eq!(Some(&1), h.get("one"));
+        assert_eq!(1, h.len());
+    }
+
+    #[test]
+    fn increments_if_present() {
+        let mut under_test = fixture();
+        let mut expected = fixture();
+
+        increment_word(&mut under_test, "three".to_string());
+        expected.insert("three".to_string(), 4);
+        assert_eq!(expected, under_test);
+    }
+
+    #[test]
+    fn insert_if_absent() {
+        let mut under_test = fixture();
+        let mut expected = fixture();
+
+        increment_word(&mut under_test, "one".to_string());
+        expected.insert("one".to_string(), 1);
+        assert_eq!(expected, under_test);
+    }
+
+    fn fixture() -> CountTable {
+        let mut h = CountTable::new();
+        h.insert("two".to_string(), 2);
+        h.insert("three".to_string(), 3);
+        
+        assert_eq!(None, h.get("one"));
+        assert_eq!(Some(&2), h.get("two"));
+        assert_eq!(Some(&3), h.get("three"));
+        assert_eq!(2, h.len());
+        h
+    }
+}
\ No newline at end of file
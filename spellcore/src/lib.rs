@@ -0,0 +1,33 @@
+#![doc="
+Shared building blocks for the spelling correctors in this repository.
+
+* `input` reads words from a training file or stdin and counts them into a
+  `CountTable`.
+* `corrector` defines the `Corrector` trait implemented by every engine and
+  the `Correction` it returns for each word.
+* `distance` measures the edit distance between two words.
+* `ngram` scores words by the words around them with a trigram language model.
+* `checker` checks every word of a text with a `Corrector`, using the context
+  of each word when asked to.
+* `confusion` reads the confusion sets used to find misused real words.
+* `segment` splits run-together words into known words.
+* `case` re-cases corrections to match how the misspelled word was written.
+* `phonetic` finds known words that sound like a misspelled word.
+* `cli` parses the options shared by the corrector binaries and prints their
+  output.
+"]
+
+pub mod case;
+pub mod checker;
+pub mod cli;
+pub mod confusion;
+pub mod corrector;
+pub mod distance;
+pub mod input;
+pub mod ngram;
+pub mod phonetic;
+pub mod segment;
+
+pub use checker::Checker;
+pub use corrector::{Correction, Corrector, Suggestion};
+pub use input::CountTable;
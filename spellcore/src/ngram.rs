@@ -0,0 +1,185 @@
+#![doc="
+A word trigram language model trained on the words of the training file, in
+order, to score a word by the words around it.
+
+Scores use stupid backoff: the fraction of times the two previous words were
+followed by the word, or, if the three words were never seen together, 0.4
+times the score given only the previous word, and so on down to the word's
+own frequency. Words never seen in training get add one smoothing, so no score
+is zero. The scores are not probabilities, but rank words like them.
+
+ex. after training on `fill in the form ... came from home`,
+  `context_score(\"came _ home\", \"from\")` > `context_score(\"came _ home\", \"form\")`
+"]
+
+use std::collections::HashMap;
+
+// How much the score of a shorter n-gram counts when the longer one was never
+// seen
+const BACKOFF: f64 = 0.4;
+
+#[derive(Default)]
+pub struct LanguageModel {
+    // Counts of single words, and of pairs and triples of consecutive words
+    // joined by spaces
+    unigrams: HashMap<String, usize>,
+    bigrams: HashMap<String, usize>,
+    trigrams: HashMap<String, usize>,
+    total: usize,
+}
+
+impl LanguageModel {
+
+    pub fn from_words(words: &[String]) -> LanguageModel {
+        let mut model = LanguageModel::default();
+        for (i, w) in words.iter().enumerate() {
+            *model.unigrams.entry(w.to_string()).or_insert(0) += 1;
+            if i >= 1 {
+                *model.bigrams.entry(format!("{} {}", words[i-1], w)).or_insert(0) += 1;
+            }
+            if i >= 2 {
+                let trigram = format!("{} {} {}", words[i-2], words[i-1], w);
+                *model.trigrams.entry(trigram).or_insert(0) += 1;
+            }
+        }
+        model.total = words.len();
+        model
+    }
+
+    // How many times word was seen in training
+    pub fn count(&self, word: &str) -> usize {
+        get(&self.unigrams, word)
+    }
+
+    // How many times first was followed by second in training
+    pub fn bigram_count(&self, first: &str, second: &str) -> usize {
+        get(&self.bigrams, &format!("{} {}", first, second))
+    }
+
+    // Whether candidate was seen in training next to either neighbour of
+    // words[i]
+    pub fn seen_in_context(&self, words: &[String], i: usize, candidate: &str) -> bool {
+        let before = i.checked_sub(1).and_then(|j| words.get(j));
+        let after = words.get(i + 1);
+        before.is_some_and(|w| get(&self.bigrams, &format!("{} {}", w, candidate)) > 0)
+            || after.is_some_and(|w| get(&self.bigrams, &format!("{} {}", candidate, w)) > 0)
+    }
+
+    // The stupid backoff score of word following prev2 then prev
+    pub fn score(&self, prev2: Option<&str>, prev: Option<&str>, word: &str) -> f64 {
+        if let (Some(p2), Some(p)) = (prev2, prev) {
+            let history = get(&self.bigrams, &format!("{} {}", p2, p));
+            let count = get(&self.trigrams, &format!("{} {} {}", p2, p, word));
+            if count > 0 {
+                return count as f64 / history as f64;
+            }
+        }
+        if let Some(p) = prev {
+            let history = get(&self.unigrams, p);
+            let count = get(&self.bigrams, &format!("{} {}", p, word));
+            if count > 0 {
+                let backoff = if prev2.is_some() { BACKOFF } else { 1.0 };
+                return backoff * count as f64 / history as f64;
+            }
+        }
+        let backoff = match (prev2, prev) {
+            (Some(_), Some(_)) => BACKOFF * BACKOFF,
+            (_, Some(_)) => BACKOFF,
+            _ => 1.0,
+        };
+        let count = get(&self.unigrams, word);
+        backoff * (count + 1) as f64 / (self.total + self.unigrams.len()) as f64
+    }
+
+    // How well candidate fits in place of words[i]: the product of the scores
+    // of every trigram that includes it
+    pub fn context_score(&self, words: &[String], i: usize, candidate: &str) -> f64 {
+        let at = |j: usize| -> Option<&str> {
+            if j == i {
+                Some(candidate)
+            } else {
+                words.get(j).map(|w| &w[..])
+            }
+        };
+        let before = |n: usize| i.checked_sub(n).and_then(at);
+        let mut score = self.score(before(2), before(1), candidate);
+        if let Some(next) = at(i + 1) {
+            score *= self.score(before(1), Some(candidate), next);
+        }
+        if let (Some(next), Some(next2)) = (at(i + 1), at(i + 2)) {
+            score *= self.score(Some(candidate), Some(next), next2);
+        }
+        score
+    }
+
+}
+
+fn get(counts: &HashMap<String, usize>, key: &str) -> usize {
+    counts.get(key).cloned().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod ngram_tests {
+
+    use super::LanguageModel;
+
+    fn words(s: &str) -> Vec<String> {
+        s.split_whitespace().map(|w| w.to_string()).collect()
+    }
+
+    fn fixture() -> LanguageModel {
+        LanguageModel::from_words(&words("please fill in the form and the form \
+                                          will be sent when i came from home"))
+    }
+
+    #[test]
+    fn scores_seen_trigrams_by_their_history() {
+        let lm = fixture();
+        assert_eq!(lm.score(Some("in"), Some("the"), "form"), 1.0);
+        assert_eq!(lm.score(Some("and"), Some("the"), "form"), 1.0);
+        assert_eq!(lm.score(None, Some("the"), "form"), 1.0);
+        assert_eq!(lm.score(None, Some("i"), "came"), 1.0);
+    }
+
+    #[test]
+    fn backs_off_to_shorter_ngrams() {
+        let lm = fixture();
+        assert_eq!(lm.score(Some("fill"), Some("the"), "form"), 0.4);
+        let unigram = lm.score(None, None, "form");
+        assert_eq!(lm.score(Some("i"), Some("came"), "form"), 0.4 * 0.4 * unigram);
+        assert!(lm.score(None, None, "unseen") > 0.0);
+        assert!(lm.score(None, None, "unseen") < unigram);
+    }
+
+    #[test]
+    fn prefers_words_that_fit_the_context() {
+        let lm = fixture();
+        let text = words("i came form home");
+        assert!(lm.context_score(&text, 2, "from") > lm.context_score(&text, 2, "form"));
+        let text = words("fill in the from");
+        assert!(lm.context_score(&text, 3, "form") > lm.context_score(&text, 3, "from"));
+    }
+
+    #[test]
+    fn knows_words_seen_next_to_their_neighbours() {
+        let lm = fixture();
+        let text = words("i came form home");
+        assert!(lm.seen_in_context(&text, 2, "from"));
+        assert!(!lm.seen_in_context(&text, 2, "form"));
+        assert!(!lm.seen_in_context(&words("came"), 0, "came"));
+        assert_eq!(lm.count("form"), 2);
+        assert_eq!(lm.count("forms"), 0);
+        assert_eq!(lm.bigram_count("the", "form"), 2);
+        assert_eq!(lm.bigram_count("form", "the"), 0);
+    }
+
+    #[test]
+    fn scores_words_at_the_edges() {
+        let lm = fixture();
+        let text = words("from");
+        assert_eq!(lm.context_score(&text, 0, "from"), lm.score(None, None, "from"));
+        let text = words("came form");
+        assert!(lm.context_score(&text, 1, "from") > lm.context_score(&text, 1, "form"));
+    }
+
+}
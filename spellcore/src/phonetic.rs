@@ -0,0 +1,323 @@
+#![doc="
+Phonetic keys group words that sound alike, so misspellings written the way a
+word sounds, like `fonetik` or `nollij`, can be corrected even when they are
+many edits away from the word.
+
+* `Soundex` keeps the first letter of a word and encodes the next three
+  consonant sounds as digits, e.g. `R163` for both `robert` and `rupert`.
+* `Metaphone` encodes the whole word by sound using English spelling rules,
+  e.g. `FNTK` for both `phonetic` and `fonetik` and `NLJ` for both `knowledge`
+  and `nollij`. This is the original single-key Metaphone, not Double
+  Metaphone: each word gets only one key, for its usual English
+  pronunciation, so words of other origins that are also spelled the way
+  they sound in their own language, like `Schmidt` for `Smith`, aren't found.
+
+A `PhoneticIndex` maps the key of every word in the training file to the
+words with that key. Letters outside a-z are ignored when computing keys.
+"]
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::corrector::Suggestion;
+use crate::distance::damerau_levenshtein_distance;
+use crate::input::CountTable;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Phonetic {
+    Soundex,
+    Metaphone,
+}
+
+impl Phonetic {
+
+    pub fn key(&self, word: &str) -> String {
+        match *self {
+            Phonetic::Soundex => soundex(word),
+            Phonetic::Metaphone => metaphone(word),
+        }
+    }
+
+}
+
+impl FromStr for Phonetic {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Phonetic, String> {
+        match s {
+            "soundex" => Ok(Phonetic::Soundex),
+            "metaphone" => Ok(Phonetic::Metaphone),
+            _ => Err(format!("Unknown phonetic algorithm {}", s)),
+        }
+    }
+}
+
+pub struct PhoneticIndex {
+    phonetic: Phonetic,
+
+    // Maps each key to the words with that key and their frequencies
+    keys: HashMap<String, Vec<(String, usize)>>,
+}
+
+impl PhoneticIndex {
+
+    pub fn new(phonetic: Phonetic, word_library: &CountTable) -> PhoneticIndex {
+        let mut keys: HashMap<String, Vec<(String, usize)>> = HashMap::new();
+        for (word, &freq) in word_library {
+            let key = phonetic.key(word);
+            if !key.is_empty() {
+                keys.entry(key).or_default().push((word.to_string(), freq));
+            }
+        }
+        PhoneticIndex {
+            phonetic,
+            keys,
+        }
+    }
+
+    // Up to k words that sound like word, closest and most frequent first
+    pub fn lookup(&self, word: &str, k: usize) -> Vec<Suggestion> {
+        let mut v: Vec<Suggestion> = self.keys.get(&self.phonetic.key(word)).into_iter()
+            .flatten()
+            .filter(|&(w, _)| w != word)
+            .map(|(w, freq)| Suggestion::new(w, damerau_levenshtein_distance(word, w), *freq))
+            .collect();
+        v.sort_by(Suggestion::rank);
+        v.truncate(k);
+        v
+    }
+
+}
+
+fn letters(word: &str) -> Vec<char> {
+    word.chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+pub fn soundex(word: &str) -> String {
+    let code = |c: char| match c {
+        'B' | 'F' | 'P' | 'V' => Some('1'),
+        'C' | 'G' | 'J' | 'K' | 'Q' | 'S' | 'X' | 'Z' => Some('2'),
+        'D' | 'T' => Some('3'),
+        'L' => Some('4'),
+        'M' | 'N' => Some('5'),
+        'R' => Some('6'),
+        _ => None,
+    };
+    let letters = letters(word);
+    let first = match letters.first() {
+        Some(&c) => c,
+        None => return String::new(),
+    };
+
+    let mut key = first.to_string();
+    let mut last = code(first);
+    for &c in &letters[1..] {
+        let digit = code(c);
+        if digit.is_some() && digit != last {
+            key.extend(digit);
+        }
+
+        // Letters with the same code separated by H or W count once, but a
+        // vowel between them separates them
+        if c != 'H' && c != 'W' {
+            last = digit;
+        }
+        if key.len() == 4 {
+            break;
+        }
+    }
+    while key.len() < 4 {
+        key.push('0');
+    }
+    key
+}
+
+pub fn metaphone(word: &str) -> String {
+    let mut w = letters(word);
+    w.dedup_by(|a, b| a == b && *a != 'C');
+
+    // Silent or changed first letters
+    match (w.first(), w.get(1)) {
+        (Some('K'), Some('N')) | (Some('G'), Some('N')) | (Some('P'), Some('N'))
+            | (Some('A'), Some('E')) | (Some('W'), Some('R')) => {
+            w.remove(0);
+        }
+        (Some('X'), _) => w[0] = 'S',
+        (Some('W'), Some('H')) => {
+            w.remove(1);
+        }
+        _ => {}
+    }
+
+    let is_vowel = |c: Option<&char>| matches!(c, Some('A' | 'E' | 'I' | 'O' | 'U'));
+    let at = |i: usize| w.get(i);
+    let after = |i: usize| w.get(i + 1);
+    let before = |i: usize| i.checked_sub(1).and_then(|j| w.get(j));
+
+    let mut key = String::new();
+    for (i, &c) in w.iter().enumerate() {
+        match c {
+            'A' | 'E' | 'I' | 'O' | 'U' => {
+                if i == 0 {
+                    key.push(c);
+                }
+            }
+            'B' => {
+                // Silent at the end after M, as in "dumb"
+                if !(i == w.len() - 1 && before(i) == Some(&'M')) {
+                    key.push('B');
+                }
+            }
+            'C' => {
+                let next = after(i);
+                if next == Some(&'I') && at(i + 2) == Some(&'A') {
+                    key.push('X');
+                } else if next == Some(&'H') {
+                    // SCH sounds like SK
+                    key.push(if before(i) == Some(&'S') { 'K' } else { 'X' });
+                } else if matches!(next, Some('I' | 'E' | 'Y')) {
+                    // Silent in SCI, SCE and SCY
+                    if before(i) != Some(&'S') {
+                        key.push('S');
+                    }
+                } else {
+                    key.push('K');
+                }
+            }
+            'D' => {
+                if after(i) == Some(&'G') && matches!(at(i + 2), Some('E' | 'Y' | 'I')) {
+                    key.push('J');
+                } else {
+                    key.push('T');
+                }
+            }
+            'G' => {
+                let next = after(i);
+                if next == Some(&'H') && !(i + 2 == w.len() || is_vowel(at(i + 2))) {
+                    // Silent in GH not at the end or before a vowel, as in "night"
+                } else if next == Some(&'N')
+                          && (i + 2 == w.len()
+                              || (at(i + 2) == Some(&'E') && at(i + 3) == Some(&'D')
+                                  && i + 4 == w.len())) {
+                    // Silent in GN and GNED at the end, as in "sign"
+                } else if before(i) == Some(&'D') && matches!(next, Some('E' | 'Y' | 'I')) {
+                    // Already part of the J of DGE
+                } else if matches!(next, Some('I' | 'E' | 'Y')) {
+                    key.push('J');
+                } else {
+                    key.push('K');
+                }
+            }
+            'H' => {
+                let silent = matches!(before(i), Some('C' | 'S' | 'P' | 'T' | 'G'))
+                    || (is_vowel(before(i)) && !is_vowel(after(i)));
+                if !silent {
+                    key.push('H');
+                }
+            }
+            'K' => {
+                if before(i) != Some(&'C') {
+                    key.push('K');
+                }
+            }
+            'P' => key.push(if after(i) == Some(&'H') { 'F' } else { 'P' }),
+            'Q' => key.push('K'),
+            'S' => {
+                let next = after(i);
+                if next == Some(&'H')
+                    || (next == Some(&'I') && matches!(at(i + 2), Some('O' | 'A'))) {
+                    key.push('X');
+                } else {
+                    key.push('S');
+                }
+            }
+            'T' => {
+                let next = after(i);
+                if next == Some(&'I') && matches!(at(i + 2), Some('O' | 'A')) {
+                    key.push('X');
+                } else if next == Some(&'H') {
+                    key.push('0');
+                } else if !(next == Some(&'C') && at(i + 2) == Some(&'H')) {
+                    key.push('T');
+                }
+            }
+            'V' => key.push('F'),
+            'W' | 'Y' => {
+                if is_vowel(after(i)) {
+                    key.push(c);
+                }
+            }
+            'X' => key.push_str("KS"),
+            'Z' => key.push('S'),
+            _ => key.push(c),
+        }
+    }
+    key
+}
+
+#[cfg(test)]
+mod phonetic_tests {
+
+    use crate::corrector::Suggestion;
+    use crate::input::CountTable;
+    use super::{metaphone, soundex, Phonetic, PhoneticIndex};
+
+    #[test]
+    fn test_soundex() {
+        assert_eq!(soundex("robert"), "R163");
+        assert_eq!(soundex("rupert"), "R163");
+        assert_eq!(soundex("rubin"), "R150");
+        assert_eq!(soundex("ashcraft"), "A261");
+        assert_eq!(soundex("tymczak"), "T522");
+        assert_eq!(soundex("pfister"), "P236");
+        assert_eq!(soundex("a"), "A000");
+        assert_eq!(soundex(""), "");
+    }
+
+    #[test]
+    fn test_metaphone() {
+        assert_eq!(metaphone("phonetic"), "FNTK");
+        assert_eq!(metaphone("fonetik"), "FNTK");
+        assert_eq!(metaphone("knowledge"), "NLJ");
+        assert_eq!(metaphone("nollij"), "NLJ");
+        assert_eq!(metaphone("thumb"), "0M");
+        assert_eq!(metaphone("night"), "NT");
+        assert_eq!(metaphone("science"), "SNS");
+        assert_eq!(metaphone("school"), "SKL");
+        assert_eq!(metaphone("nation"), "NXN");
+        assert_eq!(metaphone("whistle"), "WSTL");
+        assert_eq!(metaphone("xylophone"), "SLFN");
+        assert_eq!(metaphone("aeon"), "EN");
+        assert_eq!(metaphone("sign"), "SN");
+        assert_eq!(metaphone("café"), "KF");
+
+        // One key per word, so alternate pronunciations don't match
+        assert_ne!(metaphone("schmidt"), metaphone("smith"));
+    }
+
+    #[test]
+    fn parses_algorithm_names() {
+        assert_eq!(Ok(Phonetic::Soundex), "soundex".parse());
+        assert_eq!(Ok(Phonetic::Metaphone), "metaphone".parse());
+        assert!("nysiis".parse::<Phonetic>().is_err());
+    }
+
+    #[test]
+    fn looks_up_words_that_sound_alike() {
+        let mut h = CountTable::new();
+        h.insert("phonetic".to_string(), 2);
+        h.insert("fanatic".to_string(), 1);
+        h.insert("knowledge".to_string(), 5);
+        h.insert("phonetics".to_string(), 1);
+        let index = PhoneticIndex::new(Phonetic::Metaphone, &h);
+        assert_eq!(index.lookup("fonetik", 3),
+                   vec![Suggestion::new("phonetic", 3, 2), Suggestion::new("fanatic", 3, 1)]);
+        assert_eq!(index.lookup("nollij", 3), vec![Suggestion::new("knowledge", 6, 5)]);
+        assert!(index.lookup("phonetic", 3).iter().all(|s| s.word != "phonetic"));
+        assert!(index.lookup("zzz", 3).is_empty());
+    }
+
+}
@@ -0,0 +1,137 @@
+#![doc="
+Splits run-together words, like `thequickbrown` or the hashtag
+`#spellingcorrector`, into the most probable sequence of known words.
+
+Each known word has probability count / total in the training file, and a
+split's probability is the product of its words' probabilities. The Viterbi
+algorithm finds the most probable split of every prefix of the input in turn,
+from the best splits of the shorter prefixes, so it only tries each possible
+last word once per prefix. Pieces that aren't known words are not allowed, so
+some inputs can't be split at all.
+"]
+
+use crate::input::CountTable;
+
+pub struct Segmenter {
+    counts: CountTable,
+    total: usize,
+
+    // The most letters in a known word, the longest piece worth trying
+    longest: usize,
+}
+
+impl Segmenter {
+
+    pub fn new(counts: CountTable) -> Segmenter {
+        let total = counts.values().sum();
+        let longest = counts.keys().map(|w| w.chars().count()).max().unwrap_or(0);
+        Segmenter {
+            counts,
+            total,
+            longest,
+        }
+    }
+
+    pub fn frequency(&self, word: &str) -> usize {
+        self.counts.get(word).cloned().unwrap_or(0)
+    }
+
+    // The most probable split of word into two or more known words, if it has
+    // one
+    pub fn segment(&self, word: &str) -> Option<Vec<String>> {
+        // Byte offsets of the splits between letters, including both ends
+        let mut splits: Vec<usize> = word.char_indices().map(|(i, _)| i).collect();
+        splits.push(word.len());
+        let n = splits.len() - 1;
+
+        // best[j] is the log probability of the best split of the first j
+        // letters, and start[j] where its last word starts
+        let mut best: Vec<Option<f64>> = vec![None; n+1];
+        let mut start = vec![0; n+1];
+        best[0] = Some(0.0);
+        for j in 1..(n+1) {
+            for i in j.saturating_sub(self.longest)..j {
+                let prefix = match best[i] {
+                    Some(p) => p,
+                    None => continue,
+                };
+                let count = self.frequency(&word[splits[i]..splits[j]]);
+                if count == 0 {
+                    continue;
+                }
+                let p = prefix + (count as f64 / self.total as f64).ln();
+                if best[j].is_none_or(|b| p > b) {
+                    best[j] = Some(p);
+                    start[j] = i;
+                }
+            }
+        }
+        best[n]?;
+
+        let mut words = Vec::new();
+        let mut j = n;
+        while j > 0 {
+            words.push(word[splits[start[j]]..splits[j]].to_string());
+            j = start[j];
+        }
+        words.reverse();
+        if words.len() < 2 {
+            return None;
+        }
+        Some(words)
+    }
+
+}
+
+#[cfg(test)]
+mod segment_tests {
+
+    use crate::input::CountTable;
+    use super::Segmenter;
+
+    fn fixture() -> Segmenter {
+        let mut counts = CountTable::new();
+        for (w, c) in &[("the", 50), ("quick", 5), ("brown", 5), ("fox", 3), ("a", 30),
+                        ("spelling", 4), ("corrector", 2), ("in", 20), ("to", 20),
+                        ("therapist", 1), ("rapist", 1), ("niño", 2), ("año", 2)] {
+            counts.insert(w.to_string(), *c);
+        }
+        Segmenter::new(counts)
+    }
+
+    fn split(words: &[&str]) -> Option<Vec<String>> {
+        Some(words.iter().map(|w| w.to_string()).collect())
+    }
+
+    #[test]
+    fn splits_run_together_words() {
+        let s = fixture();
+        assert_eq!(s.segment("thequickbrown"), split(&["the", "quick", "brown"]));
+        assert_eq!(s.segment("spellingcorrector"), split(&["spelling", "corrector"]));
+        assert_eq!(s.segment("intothe"), split(&["in", "to", "the"]));
+    }
+
+    #[test]
+    fn prefers_the_most_probable_split() {
+        let s = fixture();
+        assert_eq!(s.segment("thequickbrownfoxtherapist"),
+                   split(&["the", "quick", "brown", "fox", "therapist"]));
+        assert_eq!(s.segment("atherapist"), split(&["a", "therapist"]));
+    }
+
+    #[test]
+    fn does_not_split_known_or_unsplittable_words() {
+        let s = fixture();
+        assert_eq!(s.segment("quick"), None);
+        assert_eq!(s.segment("thequixkbrown"), None);
+        assert_eq!(s.segment(""), None);
+    }
+
+    #[test]
+    fn splits_non_ascii_words() {
+        let s = fixture();
+        assert_eq!(s.segment("elniño"), None);
+        assert_eq!(s.segment("niñoaño"), split(&["niño", "año"]));
+    }
+
+}
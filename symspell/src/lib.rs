@@ -0,0 +1,10 @@
+#![doc="
+SymSpell spelling corrector.
+
+See the `symspell` module for how the deletes of the training words are
+indexed and looked up.
+"]
+
+pub mod symspell;
+
+pub use crate::symspell::{SymSpell, DEFAULT_MAX_DISTANCE};
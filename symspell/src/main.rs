@@ -0,0 +1,53 @@
+#![doc="
+Input:
+* Counts the words in the given training file and indexes each word under
+  every string made by deleting up to 2 of its letters (or up to
+  `--max-distance` letters). Words in the training file are handled based on
+  the rules specified in the input module.
+* The program then reads words from stdin. If the word was in the training
+  file, it is assumed to be spelled correctly. If the word was not in the
+  training file, the program looks up the deletes of the input word to find the
+  word with the least edit distance between it and the input word. In the case
+  of a tie, the word with the highest frequency in the training file is
+  chosen. If no words are found within an edit distance of 2, the program is
+  unable to make a suggestion.
+
+Output:
+* The program prints each word from stdin on a separate line.
+* If the word was found in the training file, it is printed alone.
+* If the word was not found in the training file and the program found a
+  suggested correction, the suggested word is printed next to the input word.
+* If the word was not found in the training file but the program did not find
+  a suggested correction, a dash is printed next to the input word.
+
+Like the Norvig corrector, a transposition of adjacent letters is a single
+edit, so the SymSpell corrector almost always suggests the same corrections as
+the Norvig corrector.
+
+ex.
+  bed
+  qhair, chair
+  sflj, -
+"]
+
+use std::{env, io};
+
+use spellcore::cli::{self, Args};
+use spellcore::distance::MaxDistance;
+use spellcore::input::Document;
+use symspell::{SymSpell, DEFAULT_MAX_DISTANCE};
+
+fn main() {
+    let args = Args::parse(env::args().skip(1), cli::VALUE_FLAGS, cli::SWITCH_FLAGS)
+        .unwrap_or_else(|e| panic!("{}", e));
+    let max_distance: Option<MaxDistance> = args.value("--max-distance")
+        .unwrap_or_else(|e| panic!("{}", e));
+    let training = cli::Training::read(&args);
+    let word_library = training.counts();
+    let document = Document::from_reader(io::stdin())
+        .unwrap_or_else(|e| panic!("Error reading stdin: {}", e));
+
+    let symspell = SymSpell::with_max_distance(&word_library,
+                                               max_distance.unwrap_or(DEFAULT_MAX_DISTANCE));
+    cli::print_document(&symspell, &training, &document, &args);
+}
@@ -0,0 +1,290 @@
+#![doc="
+SymSpell
+Indexes every word in the training file under each string that can be made by
+deleting up to max_distance of its letters. Deleting letters from the input
+word as well, two words are within max_distance edits of each other only if
+they share such a deletion, so a lookup only generates the deletes of the
+input word instead of every edit, and only measures the distance to the words
+indexed under them. Distances are restricted Damerau-Levenshtein distances, so
+a transposition of adjacent letters is a single edit, like in the Norvig
+corrector.
+
+The index makes lookups fast even at large distances, at the cost of a slower
+training phase and much more memory: a word of n letters has about n^d deletes
+within distance d.
+
+words: each word in the training file and its frequency
+deletes: maps the hash of each delete to the positions in words of the words
+  it was made from, including each word itself. Storing hashes instead of the
+  deletes saves most of the memory; two deletes with the same hash only add a
+  candidate, which is then measured and dropped.
+index_distance: the largest number of letters deleted from the indexed words,
+  which bounds the distance of any lookup
+max_distance: the search radius used when queried through the `Corrector`
+  trait
+"]
+
+use std::collections::{HashMap, HashSet};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use spellcore::{Correction, CountTable, Corrector, Suggestion};
+use spellcore::distance::{damerau_levenshtein_distance_within, MaxDistance};
+
+pub const DEFAULT_MAX_DISTANCE: MaxDistance = MaxDistance::Fixed(2);
+
+pub struct SymSpell {
+    words: Vec<(String, usize)>,
+    deletes: HashMap<u64, Vec<usize>>,
+    index_distance: usize,
+    max_distance: MaxDistance,
+}
+
+impl SymSpell {
+
+    pub fn new(word_library: &CountTable) -> SymSpell {
+        SymSpell::with_max_distance(word_library, DEFAULT_MAX_DISTANCE)
+    }
+
+    // Indexes deletes deep enough to look up any training word within
+    // max_distance. Longer input words are looked up at the largest distance
+    // indexed. No word has more deletes than letters, so no word needs
+    // indexing deeper than its length.
+    pub fn with_max_distance(word_library: &CountTable, max_distance: MaxDistance) -> SymSpell {
+        let index_distance = word_library.keys()
+            .map(|w| max_distance.for_word(w).min(w.chars().count()))
+            .max()
+            .unwrap_or(0);
+
+        let mut words = Vec::with_capacity(word_library.len());
+        let mut deletes: HashMap<u64, Vec<usize>> = HashMap::new();
+        for (word, &freq) in word_library {
+            for delete in deletes_within(word, index_distance) {
+                deletes.entry(hash(&delete)).or_default().push(words.len());
+            }
+            words.push((word.to_string(), freq));
+        }
+
+        SymSpell {
+            words,
+            deletes,
+            index_distance,
+            max_distance,
+        }
+    }
+
+    pub fn set_max_distance(&mut self, max_distance: MaxDistance) {
+        self.max_distance = max_distance;
+    }
+
+    // All words within distance d of word, best first
+    pub fn candidates(&self, word: &str, d: usize) -> Vec<Suggestion> {
+        let d = d.min(self.index_distance);
+
+        // Several deletes of the input can lead to the same word
+        let mut seen = HashSet::new();
+        let mut v = Vec::new();
+        for delete in deletes_within(word, d) {
+            for &i in self.deletes.get(&hash(&delete)).into_iter().flatten() {
+                if !seen.insert(i) {
+                    continue;
+                }
+                let (ref w, freq) = self.words[i];
+                if let Some(dist) = damerau_levenshtein_distance_within(word, w, d) {
+                    v.push(Suggestion::new(w, dist, freq));
+                }
+            }
+        }
+        v.sort_by(Suggestion::rank);
+        v
+    }
+
+    pub fn search(&self, word: &str, d: usize) -> Correction {
+        match self.candidates(word, d).into_iter().next() {
+            Some(best) => best.into_correction(),
+            None => Correction::NoSuggestion,
+        }
+    }
+
+}
+
+impl Corrector for SymSpell {
+
+    fn check(&self, word: &str) -> bool {
+        self.deletes.get(&hash(word))
+            .is_some_and(|v| v.iter().any(|&i| self.words[i].0 == word))
+    }
+
+    fn suggest(&self, word: &str) -> Correction {
+        self.search(word, self.max_distance.for_word(word))
+    }
+
+    fn suggest_top_k(&self, word: &str, k: usize) -> Vec<Suggestion> {
+        let mut v = self.candidates(word, self.max_distance.for_word(word));
+        v.truncate(k);
+        v
+    }
+
+    fn suggest_within(&self, word: &str, max_distance: usize, k: usize) -> Vec<Suggestion> {
+        let mut v = self.candidates(word, max_distance.min(self.max_distance.for_word(word)));
+        v.truncate(k);
+        v
+    }
+
+}
+
+fn hash(delete: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    delete.hash(&mut hasher);
+    hasher.finish()
+}
+
+// The word itself and every distinct string made by deleting up to d of its
+// letters
+fn deletes_within(word: &str, d: usize) -> HashSet<String> {
+    let mut all = HashSet::new();
+    all.insert(word.to_string());
+    let mut level = vec![word.to_string()];
+    for _ in 0..d {
+        if level.is_empty() {
+            break;
+        }
+        let mut next = Vec::new();
+        for w in &level {
+            for (i, c) in w.char_indices() {
+                let delete = format!("{}{}", &w[..i], &w[i + c.len_utf8()..]);
+                if all.insert(delete.clone()) {
+                    next.push(delete);
+                }
+            }
+        }
+        level = next;
+    }
+    all
+}
+
+#[cfg(test)]
+mod symspell_tests {
+
+    use std::collections::HashSet;
+    use spellcore::{Correction, CountTable, Corrector, Suggestion};
+    use spellcore::distance::{damerau_levenshtein_distance, MaxDistance};
+    use super::{deletes_within, SymSpell};
+
+    #[test]
+    fn test_deletes_within() {
+        let expected: HashSet<String> = ["abc", "bc", "ac", "ab"].iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(deletes_within("abc", 1), expected);
+        assert_eq!(deletes_within("abc", 3).len(), 8);
+        assert_eq!(deletes_within("aaa", 2).len(), 3);
+        assert!(deletes_within("año", 1).contains("ao"));
+        assert_eq!(deletes_within("abc", usize::MAX).len(), 8);
+    }
+
+    #[test]
+    fn test_check() {
+        let s = fixture();
+        assert!(s.check("who"));
+        assert!(!s.check("ho"));
+        assert!(!s.check("wh"));
+    }
+
+    #[test]
+    fn test_suggest() {
+        let s = fixture();
+        assert_eq!(s.suggest("who"), Correction::Known);
+        assert_eq!(s.suggest("ho"), Correction::suggested("who", 1, 2));
+        assert_eq!(s.suggest("wherf"), Correction::suggested("where", 1, 2));
+        assert_eq!(s.suggest("asjkdghlaksjdghls"), Correction::NoSuggestion);
+    }
+
+    #[test]
+    fn test_counts_transposition_once() {
+        let s = fixture();
+        assert_eq!(s.suggest("hwo"), Correction::suggested("who", 1, 2));
+    }
+
+    #[test]
+    fn test_suggest_top_k() {
+        let s = fixture();
+        assert_eq!(s.suggest_top_k("ho", 3),
+                   vec![Suggestion::new("who", 1, 2),
+                        Suggestion::new("how", 1, 1),
+                        Suggestion::new("why", 2, 1)]);
+    }
+
+    #[test]
+    fn test_lookups_are_capped_at_index_distance() {
+        let mut h = CountTable::new();
+        h.insert("three".to_string(), 1);
+        let mut s = SymSpell::with_max_distance(&h, MaxDistance::Fixed(1));
+        assert_eq!(s.suggest("thre"), Correction::suggested("three", 1, 1));
+        s.set_max_distance(MaxDistance::Fixed(3));
+        assert_eq!(s.suggest("thr"), Correction::NoSuggestion);
+    }
+
+    #[test]
+    fn test_index_is_capped_at_word_length() {
+        let mut h = CountTable::new();
+        for w in &["what", "where", "who"] {
+            h.insert(w.to_string(), 1);
+        }
+        let s = SymSpell::with_max_distance(&h, MaxDistance::Fixed(usize::MAX));
+        assert_eq!(s.index_distance, 5);
+        assert_eq!(s.candidates("xyz", usize::MAX).len(), 3);
+        assert_eq!(s.suggest("whxxe"), Correction::suggested("where", 2, 1));
+    }
+
+    #[test]
+    fn test_drops_words_further_than_the_limit() {
+        // "bca" and "abc" share the delete "bc" but are two edits apart
+        let mut h = CountTable::new();
+        h.insert("abc".to_string(), 1);
+        let s = SymSpell::with_max_distance(&h, MaxDistance::Fixed(2));
+        assert!(s.candidates("bca", 1).is_empty());
+        assert_eq!(s.candidates("bca", 2), vec![Suggestion::new("abc", 2, 1)]);
+        assert_eq!(s.candidates("bac", 1), vec![Suggestion::new("abc", 1, 1)]);
+    }
+
+    #[test]
+    fn test_finds_same_words_as_exhaustive_search() {
+        let words = ["the", "then", "than", "that", "this", "these", "those", "there",
+                     "their", "they", "them", "thee", "three", "throw", "through",
+                     "though", "thought", "tough", "trough", "cough", "bough", "bought",
+                     "niño", "año", "straße", "abc", "ca"];
+        let mut h = CountTable::new();
+        for w in &words {
+            h.insert(w.to_string(), 1);
+        }
+        let s = SymSpell::with_max_distance(&h, MaxDistance::Fixed(3));
+        for query in &["th", "thew", "hte", "thought", "rough", "xyz", "thorough", "nino",
+                       "strasse", "ac", "bca"] {
+            for d in 0..4 {
+                let mut found: Vec<String> = s.candidates(query, d).into_iter()
+                    .map(|s| s.word)
+                    .collect();
+                found.sort();
+                let mut expected: Vec<String> = words.iter()
+                    .filter(|w| damerau_levenshtein_distance(w, query) <= d)
+                    .map(|w| w.to_string())
+                    .collect();
+                expected.sort();
+                assert_eq!(expected, found, "{} {}", query, d);
+            }
+        }
+    }
+
+    fn fixture() -> SymSpell {
+        let mut h = CountTable::new();
+        h.insert("what".to_string(), 1);
+        h.insert("why".to_string(), 1);
+        h.insert("where".to_string(), 2);
+        h.insert("when".to_string(), 1);
+        h.insert("how".to_string(), 1);
+        h.insert("who".to_string(), 2);
+        SymSpell::new(&h)
+    }
+
+}
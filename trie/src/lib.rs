@@ -0,0 +1,10 @@
+#![doc="
+Trie spelling corrector.
+
+See the `trie` module for how the trie is searched with one row of the
+Levenshtein distance table per node.
+"]
+
+pub mod trie;
+
+pub use crate::trie::{Trie, TrieNode, DEFAULT_MAX_DISTANCE};
@@ -0,0 +1,88 @@
+#![doc="
+Input:
+* Inserts each word in the given training file into a trie, along with the
+  number of times the word has been encountered. Words in the training file are
+  handled based on the rules specified in the input module.
+* The program then reads words from stdin. If the word was in the training
+  file, it is assumed to be spelled correctly. If the word was not in the
+  training file, the program walks the trie to find the word with the least
+  Levenshtein distance between it and the input word. In the case of a tie, the
+  word with the highest frequency in the training file is chosen. If no words
+  are found within a Levenshtein distance of 2 (see `--max-distance`), the
+  program is unable to make a suggestion.
+
+Output:
+* The program prints each word from stdin on a separate line.
+* If the word was found in the training file, it is printed alone.
+* If the word was not found in the training file and the program found a
+  suggested correction, the suggested word is printed next to the input word.
+* If the word was not found in the training file but the program did not find
+  a suggested correction, a dash is printed next to the input word.
+
+The trie corrector uses the Levenshtein distance, so it suggests the same
+corrections as the BK tree corrector.
+
+Completion:
+* `trie complete <training file>` reads prefixes from stdin instead and prints
+  each prefix followed by the 5 most frequent words starting with it (or
+  `--suggestions N` words), each with the number of typos in the prefix and
+  its frequency.
+* `--typos N` also completes prefixes within N edits of the input, after the
+  words starting with the exact prefix. The default is 0. At most all but one
+  letter of the input count as typos, so `q` never completes to `the`.
+
+ex.
+  qu, queen (0, 20), question (0, 12)
+  qeu, -
+
+ex.
+  bed
+  qhair, chair
+  sflj, -
+"]
+
+use std::{env, io};
+
+use spellcore::cli::{self, Args};
+use spellcore::distance::MaxDistance;
+use spellcore::input::Document;
+use trie::Trie;
+
+const DEFAULT_COMPLETIONS: usize = 5;
+
+fn main() {
+    let mut argv: Vec<String> = env::args().skip(1).collect();
+    let complete = argv.first().is_some_and(|a| a == "complete");
+    if complete {
+        argv.remove(0);
+    }
+    let value_flags = [cli::VALUE_FLAGS, &["--typos"]].concat();
+    let args = Args::parse(argv, &value_flags, cli::SWITCH_FLAGS)
+        .unwrap_or_else(|e| panic!("{}", e));
+    let max_distance: Option<MaxDistance> = args.value("--max-distance")
+        .unwrap_or_else(|e| panic!("{}", e));
+    let training = cli::Training::read(&args);
+    let word_library = training.counts();
+    let document = Document::from_reader(io::stdin())
+        .unwrap_or_else(|e| panic!("Error reading stdin: {}", e));
+
+    let mut trie = Trie::new();
+    for (word, &freq) in &word_library {
+        trie.insert(word, freq);
+    }
+    if let Some(max_distance) = max_distance {
+        trie.set_max_distance(max_distance);
+    }
+
+    if complete {
+        let k: usize = args.value("--suggestions").unwrap_or_else(|e| panic!("{}", e))
+            .unwrap_or(DEFAULT_COMPLETIONS);
+        let typos: usize = args.value("--typos").unwrap_or_else(|e| panic!("{}", e))
+            .unwrap_or(0);
+        for prefix in document.tokens.iter().map(|t| &t.key) {
+            println!("{}", cli::format_suggestions(prefix, &trie.complete(prefix, k, typos)));
+        }
+    } else {
+        cli::print_document(&trie, &training, &document, &args);
+    }
+}
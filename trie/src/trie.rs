@@ -0,0 +1,402 @@
+#![doc="
+Trie
+Stores the words of the training file letter by letter, so words sharing a
+prefix share the path to it. A search walks the trie depth first keeping one
+row of the Levenshtein distance table per node: the distances between the
+prefix spelled by the path so far and every prefix of the input word. A child's
+row is computed from its parent's, so each prefix is only measured once, and a
+branch is abandoned as soon as every entry in its row exceeds the search
+radius, since no longer word below it can be any closer.
+
+The search finds the same words as a BK tree using the Levenshtein distance.
+
+Completion finds the most frequent words starting with a prefix. With typos
+allowed, the same rows find every node whose path is within that many edits of
+the prefix, and the words below it are completions too.
+
+root: the empty prefix; every word is a path from it
+max_distance: the search radius used when the trie is queried through the
+  `Corrector` trait
+longest: the number of chars in the longest word, which caps the radius
+"]
+
+use std::collections::BTreeMap;
+
+use spellcore::{Correction, Corrector, Suggestion};
+use spellcore::distance::MaxDistance;
+
+pub const DEFAULT_MAX_DISTANCE: MaxDistance = MaxDistance::Fixed(2);
+
+#[derive(Default)]
+pub struct TrieNode {
+    // The frequency of the word ending here, if one does
+    freq: Option<usize>,
+    children: BTreeMap<char, TrieNode>,
+}
+
+pub struct Trie {
+    root: TrieNode,
+    max_distance: MaxDistance,
+    longest: usize,
+}
+
+impl Default for Trie {
+    fn default() -> Trie {
+        Trie::new()
+    }
+}
+
+impl Trie {
+
+    pub fn new() -> Trie {
+        Trie {
+            root: TrieNode::default(),
+            max_distance: DEFAULT_MAX_DISTANCE,
+            longest: 0,
+        }
+    }
+
+    pub fn add(&mut self, word: &str) {
+        self.insert(word, 1);
+    }
+
+    // Adds freq to the frequency of word
+    pub fn insert(&mut self, word: &str, freq: usize) {
+        self.longest = self.longest.max(word.chars().count());
+        let mut node = &mut self.root;
+        for c in word.chars() {
+            node = node.children.entry(c).or_default();
+        }
+        *node.freq.get_or_insert(0) += freq;
+    }
+
+    pub fn set_max_distance(&mut self, max_distance: MaxDistance) {
+        self.max_distance = max_distance;
+    }
+
+    // The frequency of word, if it is in the trie
+    pub fn get(&self, word: &str) -> Option<usize> {
+        self.find_node(word).and_then(|node| node.freq)
+    }
+
+    // Every word starting with prefix and its frequency, in alphabetical order
+    pub fn with_prefix(&self, prefix: &str) -> Vec<(String, usize)> {
+        let mut v = Vec::new();
+        if let Some(node) = self.find_node(prefix) {
+            collect_words(node, &mut prefix.to_string(), &mut v);
+        }
+        v
+    }
+
+    // Up to k words starting with prefix, or with a prefix within typos edits of
+    // it, ranked by those edits and then by frequency. Each suggestion's
+    // distance is the number of typos in the prefix. At least one letter of
+    // the prefix has to be right, or every word would complete it.
+    pub fn complete(&self, prefix: &str, k: usize, typos: usize) -> Vec<Suggestion> {
+        let prefix: Vec<char> = prefix.chars().collect();
+        let typos = typos.min(prefix.len().saturating_sub(1));
+        let first_row: Vec<usize> = (0..(prefix.len()+1)).collect();
+        let mut v = Vec::new();
+        let best = prefix.len();
+        if best <= typos {
+            if let Some(freq) = self.root.freq {
+                v.push(Suggestion::new("", best, freq));
+            }
+        }
+        for (&c, child) in &self.root.children {
+            let mut path = String::new();
+            complete_row(child, c, &prefix, &first_row, best, typos, &mut path, &mut v);
+        }
+        v.sort_by(Suggestion::rank);
+        v.truncate(k);
+        v
+    }
+
+    pub fn search(&self, word: &str, d: &usize) -> Correction {
+        // the first candidate is an exact match (ie. dist == 0) if there is one
+        match self.candidates(word, d).into_iter().next() {
+            Some(best) => best.into_correction(),
+            None => Correction::NoSuggestion,
+        }
+    }
+
+    // All words within Levenshtein distance d of word, best first
+    pub fn candidates(&self, word: &str, d: &usize) -> Vec<Suggestion> {
+        let word: Vec<char> = word.chars().collect();
+        let first_row: Vec<usize> = (0..(word.len()+1)).collect();
+        let mut v = Vec::new();
+        if word.len() <= *d {
+            if let Some(freq) = self.root.freq {
+                v.push(Suggestion::new("", word.len(), freq));
+            }
+        }
+        for (&c, child) in &self.root.children {
+            let mut prefix = String::new();
+            search_row(child, c, &word, &first_row, *d, &mut prefix, &mut v);
+        }
+        v.sort_by(Suggestion::rank);
+        v
+    }
+
+    fn find_node(&self, prefix: &str) -> Option<&TrieNode> {
+        let mut node = &self.root;
+        for c in prefix.chars() {
+            node = node.children.get(&c)?;
+        }
+        Some(node)
+    }
+
+}
+
+impl Corrector for Trie {
+
+    fn check(&self, word: &str) -> bool {
+        self.get(word).is_some()
+    }
+
+    fn suggest(&self, word: &str) -> Correction {
+        self.search(word, &self.max_distance.within(word, self.longest))
+    }
+
+    fn suggest_top_k(&self, word: &str, k: usize) -> Vec<Suggestion> {
+        let mut v = self.candidates(word, &self.max_distance.within(word, self.longest));
+        v.truncate(k);
+        v
+    }
+
+    fn suggest_within(&self, word: &str, max_distance: usize, k: usize) -> Vec<Suggestion> {
+        let d = max_distance.min(self.max_distance.within(word, self.longest));
+        let mut v = self.candidates(word, &d);
+        v.truncate(k);
+        v
+    }
+
+}
+
+fn collect_words(node: &TrieNode, prefix: &mut String, v: &mut Vec<(String, usize)>) {
+    if let Some(freq) = node.freq {
+        v.push((prefix.clone(), freq));
+    }
+    for (&c, child) in &node.children {
+        prefix.push(c);
+        collect_words(child, prefix, v);
+        prefix.pop();
+    }
+}
+
+// Computes the row for node, reached from its parent by letter c, from the
+// parent's row prev
+fn search_row(node: &TrieNode,
+              c: char,
+              word: &[char],
+              prev: &[usize],
+              d: usize,
+              prefix: &mut String,
+              v: &mut Vec<Suggestion>) {
+    prefix.push(c);
+
+    let mut row = Vec::with_capacity(prev.len());
+    row.push(prev[0] + 1);
+    for j in 1..prev.len() {
+        let substitute = if word[j-1] == c { 0 } else { 1 };
+        let dist = (prev[j] + 1).min(row[j-1] + 1).min(prev[j-1] + substitute);
+        row.push(dist);
+    }
+
+    let dist = row[word.len()];
+    if dist <= d {
+        if let Some(freq) = node.freq {
+            v.push(Suggestion::new(prefix, dist, freq));
+        }
+    }
+
+    // Letters added below this node can only move away from the smallest
+    // distance in the row
+    if row.iter().min().is_some_and(|&m| m <= d) {
+        for (&c, child) in &node.children {
+            search_row(child, c, word, &row, d, prefix, v);
+        }
+    }
+
+    prefix.pop();
+}
+
+// Like search_row, but once the path is within typos edits of the whole
+// prefix (best is the fewest edits of any path to this node), every word
+// below is a completion
+#[allow(clippy::too_many_arguments)]
+fn complete_row(node: &TrieNode,
+                c: char,
+                prefix: &[char],
+                prev: &[usize],
+                best: usize,
+                typos: usize,
+                path: &mut String,
+                v: &mut Vec<Suggestion>) {
+    path.push(c);
+
+    let mut row = Vec::with_capacity(prev.len());
+    row.push(prev[0] + 1);
+    for j in 1..prev.len() {
+        let substitute = if prefix[j-1] == c { 0 } else { 1 };
+        row.push((prev[j] + 1).min(row[j-1] + 1).min(prev[j-1] + substitute));
+    }
+
+    let best = best.min(row[prefix.len()]);
+    if best <= typos {
+        if let Some(freq) = node.freq {
+            v.push(Suggestion::new(path, best, freq));
+        }
+    }
+
+    if best <= typos || row.iter().min().is_some_and(|&m| m <= typos) {
+        for (&c, child) in &node.children {
+            complete_row(child, c, prefix, &row, best, typos, path, v);
+        }
+    }
+
+    path.pop();
+}
+
+#[cfg(test)]
+mod trie_tests {
+
+    use std::collections::HashMap;
+    use bktree::{BKTree, Node};
+    use spellcore::{Correction, Corrector, Suggestion};
+    use spellcore::distance::MaxDistance;
+    use super::Trie;
+
+    #[test]
+    fn test_no_need_to_correct_word() {
+        let t = small_fixture();
+        assert_eq!(t.search("who", &1), Correction::Known);
+    }
+
+    #[test]
+    fn test_no_suggestions_found() {
+        let t = small_fixture();
+        assert_eq!(t.search("asjkdghlaksjdghls", &1), Correction::NoSuggestion);
+    }
+
+    #[test]
+    fn test_chooses_more_frequent_alternative() {
+        let t = small_fixture();
+        assert_eq!(t.search("ho", &1), Correction::suggested("who", 1, 2));
+    }
+
+    #[test]
+    fn test_check() {
+        let t = small_fixture();
+        assert!(t.check("who"));
+        assert!(!t.check("wh"));
+        assert!(!t.check("ho"));
+    }
+
+    #[test]
+    fn test_suggest_top_k() {
+        let mut t = small_fixture();
+        assert_eq!(t.suggest_top_k("who", 3),
+                   vec![Suggestion::new("who", 0, 2),
+                        Suggestion::new("why", 1, 1),
+                        Suggestion::new("how", 2, 1)]);
+        t.set_max_distance(MaxDistance::Fixed(1));
+        assert_eq!(t.suggest_top_k("ho", 3),
+                   vec![Suggestion::new("who", 1, 2), Suggestion::new("how", 1, 1)]);
+    }
+
+    #[test]
+    fn test_with_prefix() {
+        let t = small_fixture();
+        assert_eq!(t.with_prefix("wh"),
+                   vec![("what".to_string(), 1), ("when".to_string(), 1),
+                        ("where".to_string(), 2), ("who".to_string(), 2),
+                        ("why".to_string(), 1)]);
+        assert_eq!(t.with_prefix("how"), vec![("how".to_string(), 1)]);
+        assert!(t.with_prefix("x").is_empty());
+        assert_eq!(t.with_prefix("").len(), 6);
+    }
+
+    #[test]
+    fn test_complete_ranks_by_frequency() {
+        let t = small_fixture();
+        assert_eq!(t.complete("wh", 3, 0),
+                   vec![Suggestion::new("where", 0, 2),
+                        Suggestion::new("who", 0, 2),
+                        Suggestion::new("what", 0, 1)]);
+        assert_eq!(t.complete("whe", 5, 0),
+                   vec![Suggestion::new("where", 0, 2), Suggestion::new("when", 0, 1)]);
+        assert!(t.complete("x", 5, 0).is_empty());
+        assert_eq!(t.complete("", 1, 0), vec![Suggestion::new("where", 0, 2)]);
+    }
+
+    #[test]
+    fn test_complete_with_a_typo() {
+        let t = small_fixture();
+        assert!(t.complete("wje", 5, 0).is_empty());
+        assert_eq!(t.complete("wje", 5, 1),
+                   vec![Suggestion::new("where", 1, 2), Suggestion::new("when", 1, 1)]);
+
+        // Exact completions come before ones needing a typo
+        assert_eq!(t.complete("ho", 3, 1),
+                   vec![Suggestion::new("how", 0, 1), Suggestion::new("who", 1, 2)]);
+    }
+
+    #[test]
+    fn test_complete_keeps_one_letter_of_the_prefix() {
+        let t = small_fixture();
+        assert!(t.complete("q", 5, 1).is_empty());
+        assert_eq!(t.complete("h", 5, 1), vec![Suggestion::new("how", 0, 1)]);
+        assert_eq!(t.complete("hw", 10, 5), t.complete("hw", 10, 1));
+        assert!(t.complete("x", 10, 1).is_empty());
+    }
+
+    #[test]
+    fn test_complete_counts_each_word_once() {
+        let t = small_fixture();
+        let words: Vec<String> = t.complete("wh", 10, 1).into_iter().map(|s| s.word).collect();
+        let mut unique = words.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(words.len(), unique.len());
+    }
+
+    #[test]
+    fn test_non_ascii_words() {
+        let mut t = Trie::new();
+        t.add("café");
+        t.add("straße");
+        assert_eq!(t.search("cafe", &2), Correction::suggested("café", 1, 1));
+        assert_eq!(t.search("strase", &2), Correction::suggested("straße", 1, 1));
+        assert_eq!(t.with_prefix("caf"), vec![("café".to_string(), 1)]);
+    }
+
+    #[test]
+    fn test_finds_same_words_as_bk_tree() {
+        // Words sharing long prefixes, words repeated and words that aren't ASCII
+        let words = ["the", "then", "there", "three", "through", "though", "thought",
+                     "tough", "a", "niño", "año", "straße", "the", "three"];
+        let mut t = Trie::new();
+        let mut bk = BKTree::new(Node::new(words[0].to_string(), 0, HashMap::new()));
+        for w in &words {
+            t.add(w);
+            bk.add(w);
+        }
+        for query in &["", "th", "thew", "hte", "thought", "rough", "xyz", "nino", "strasse",
+                       "a"] {
+            for d in 0..5 {
+                assert_eq!(bk.candidates(query, &d), t.candidates(query, &d), "{} {}", query, d);
+                assert_eq!(bk.search(query, &d), t.search(query, &d), "{} {}", query, d);
+            }
+        }
+    }
+
+    fn small_fixture() -> Trie {
+        let mut t = Trie::new();
+        for w in &["what", "why", "where", "where", "when", "how", "who", "who"] {
+            t.add(w);
+        }
+        t
+    }
+
+}